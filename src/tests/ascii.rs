@@ -0,0 +1,39 @@
+use world::World;
+use world::ascii;
+use world::ascii::GlyphTable;
+use tile;
+
+const ROOM: &'static str = "\
+-------
+|.....|
+|.@.M.+
+|..!..|
+-------
+";
+
+#[test]
+fn ascii_round_trip(){
+	let glyphs = GlyphTable::standard();
+	let test_world = ascii::from_ascii(ROOM, &glyphs).unwrap();
+	assert!(test_world.width() == 7 && test_world.height() == 5, "The map has the wrong size");
+	let hero = test_world.objects_at(2, 2).unwrap();
+	assert!(hero.len() == 2 && hero[0].obj == tile::Type::Floor && hero[1].obj == tile::Type::MainCharacter, "The main character is not standing on floor");
+	assert!(test_world.objects_at(6, 2).unwrap()[0].obj == tile::Type::Door, "The door was not loaded");
+	assert!(ascii::to_ascii(&test_world, &glyphs) == ROOM, "The map did not dump back to the same text");
+}
+
+#[test]
+fn custom_glyphs(){
+	let mut glyphs = GlyphTable::standard();
+	glyphs.set('#', vec![tile::Type::HorizontalWall]);
+	glyphs.set('-', vec![tile::Type::Floor, tile::Type::Door]);
+	let test_world = ascii::from_ascii("#-\n.", &glyphs).unwrap();
+	assert!(test_world.width() == 2 && test_world.number_of_tiles() == 4, "A short line was not padded");
+	assert!(test_world.objects_at(1, 0).unwrap().len() == 2, "A replaced glyph kept its old stack");
+	assert!(ascii::to_ascii(&test_world, &glyphs) == "#-\n. \n", "The custom glyphs were not used to dump the map");
+	assert!(ascii::from_ascii("..x", &glyphs).is_err(), "An unknown character was loaded");
+	let mut lonely = World::new(1, 1).unwrap();
+	lonely.put(tile::Type::Item, 0, 0).unwrap();
+	lonely.put(tile::Type::HorizontalWall, 0, 0).unwrap();
+	assert!(ascii::to_ascii(&lonely, &GlyphTable::new()) == "?\n", "An unmatched stack was not written as unknown");
+}
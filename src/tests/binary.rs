@@ -0,0 +1,58 @@
+use rustc_serialize::json::ToJson;
+use world::World;
+use world::format;
+use tile;
+
+#[test]
+fn binary_round_trip(){
+	let mut test_world = World::new(40, 30).unwrap();
+	for x in 2..20 {
+		test_world.put(tile::Type::HorizontalWall, x, 2).unwrap();
+		test_world.put(tile::Type::HorizontalWall, x, 12).unwrap();
+		for y in 3..12 {
+			test_world.put(tile::Type::Floor, x, y).unwrap();
+		}
+	}
+	for y in 3..12 {
+		test_world.put(tile::Type::VerticalWall, 1, y).unwrap();
+	}
+	test_world.put(tile::Type::Door, 1, 7).unwrap();
+	test_world.put(tile::Type::MainCharacter, 3, 3).unwrap();
+	test_world.put(tile::Type::Item, 3, 3).unwrap();
+	let bytes = test_world.to_bytes();
+	assert!(&bytes[0..4] == b"PGMB", "The binary save does not start with the magic bytes");
+	let from_binary = World::from_bytes(&bytes).unwrap();
+	let from_json = format::load(test_world.to_json()).unwrap();
+	assert!(from_binary == test_world, "The world did not survive a binary save and load");
+	assert!(from_binary == from_json, "The binary and json saves disagree");
+	assert!(bytes.len() < test_world.to_json().to_string().len(), "The binary save is bigger than the json save");
+}
+
+#[test]
+fn binary_corrupt(){
+	let mut test_world = World::new(4, 4).unwrap();
+	test_world.put(tile::Type::Floor, 1, 1).unwrap();
+	let bytes = test_world.to_bytes();
+	assert!(World::from_bytes(&bytes[0..bytes.len()-1]).is_err(), "A truncated save was loaded");
+	assert!(World::from_bytes(b"{\"width\":1}").is_err(), "Json was loaded as a binary save");
+	let mut future = bytes.clone();
+	future[4] = 99;
+	assert!(World::from_bytes(&future).is_err(), "A save from a newer release was loaded");
+	let mut extra = bytes.clone();
+	extra.push(0);
+	assert!(World::from_bytes(&extra).is_err(), "A save with trailing bytes was loaded");
+	let mut huge = bytes.clone();
+	for byte in huge[6..14].iter_mut() {
+		*byte = 0xff;
+	}
+	assert!(World::from_bytes(&huge).is_err(), "A save claiming a huge world was loaded");
+	let mut runs = bytes.clone();
+	let run_count = 19+bytes[18] as usize;
+	for byte in runs[run_count..run_count+4].iter_mut() {
+		*byte = 0xff;
+	}
+	assert!(World::from_bytes(&runs).is_err(), "A save claiming more tile runs than it holds was loaded");
+	let mut short_runs = bytes.clone();
+	short_runs[run_count+4] = 1;
+	assert!(World::from_bytes(&short_runs).is_err(), "A save whose tile runs don't cover the world was loaded");
+}
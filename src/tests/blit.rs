@@ -0,0 +1,54 @@
+use world::World;
+use world::blit::BlitMode;
+use tile;
+
+#[test]
+fn extract_region(){
+	let mut test_world = World::new(10, 10).unwrap();
+	let uid = test_world.put(tile::Type::Door, 4, 5).unwrap();
+	let outside = test_world.put(tile::Type::Floor, 0, 0).unwrap();
+	let region = test_world.extract(3, 4, 3, 3).unwrap();
+	assert!(region.width() == 3 && region.height() == 3, "The extracted region has the wrong size");
+	assert!(region.number_of_tiles() == 9, "The extracted region has the wrong number of tiles");
+	assert!(region.where_is(uid) == Some((1, 1)), "The door was not copied to its relative position");
+	assert!(region.where_is(outside) == None, "An object outside the region was copied");
+	assert!(test_world.extract(8, 8, 3, 1).is_err(), "A region hanging off the edge was extracted");
+}
+
+#[test]
+fn blit_modes(){
+	let mut prefab = World::new(2, 1).unwrap();
+	let prefab_wall = prefab.put(tile::Type::VerticalWall, 0, 0).unwrap();
+	prefab.put(tile::Type::Floor, 1, 0).unwrap();
+
+	let mut overwrite = World::new(5, 5).unwrap();
+	let old = overwrite.put(tile::Type::Door, 2, 2).unwrap();
+	let uid_map = overwrite.blit(&prefab, 2, 2, BlitMode::Overwrite).unwrap();
+	assert!(overwrite.where_is(old) == None, "Overwrite left the old object in place");
+	assert!(uid_map[&prefab_wall] != prefab_wall && overwrite.where_is(uid_map[&prefab_wall]) == Some((2, 2)), "The wall was not pasted with a new uid");
+
+	let mut merge = World::new(5, 5).unwrap();
+	merge.put(tile::Type::Door, 2, 2).unwrap();
+	merge.blit(&prefab, 2, 2, BlitMode::Merge).unwrap();
+	assert!(merge.objects_at(2, 2).unwrap().len() == 2, "Merge did not stack the pasted object");
+
+	let mut skip = World::new(5, 5).unwrap();
+	skip.put(tile::Type::Door, 2, 2).unwrap();
+	let uid_map = skip.blit(&prefab, 2, 2, BlitMode::SkipOccupied).unwrap();
+	assert!(skip.objects_at(2, 2).unwrap().len() == 1 && uid_map.len() == 1, "SkipOccupied pasted into an occupied cell");
+	assert!(skip.objects_at(3, 2).unwrap().len() == 1, "SkipOccupied did not paste into an empty cell");
+
+	assert!(skip.blit(&prefab, 4, 0, BlitMode::Merge).is_err(), "A world that does not fit was pasted");
+}
+
+#[test]
+fn blit_undo(){
+	let mut prefab = World::new(2, 2).unwrap();
+	prefab.put(tile::Type::Floor, 0, 0).unwrap();
+	prefab.put(tile::Type::Floor, 1, 1).unwrap();
+	let mut test_world = World::new(4, 4).unwrap();
+	test_world.enable_journal();
+	test_world.blit(&prefab, 1, 1, BlitMode::Merge).unwrap();
+	test_world.undo().unwrap();
+	assert!(test_world.objects_at(1, 1).unwrap().len() == 0 && test_world.objects_at(2, 2).unwrap().len() == 0, "The paste was not undone in one step");
+}
@@ -0,0 +1,40 @@
+use world::World;
+use world::chokepoints;
+use world::regions;
+use tile;
+
+#[test]
+fn corridor_between_rooms(){
+	//two 2x2 rooms joined by a corridor along y=1 from x=2 to x=4
+	let mut test_world = World::new(7, 3).unwrap();
+	for &x in [0u32, 1, 5, 6].iter() {
+		for y in 0..2 {
+			test_world.put(tile::Type::Floor, x, y).unwrap();
+		}
+	}
+	for x in 2..5 {
+		test_world.put(tile::Type::Floor, x, 1).unwrap();
+	}
+	let choke_points = chokepoints::find_choke_points(&test_world);
+	assert!(choke_points.articulation_cells == vec![(1, 1), (2, 1), (3, 1), (4, 1), (5, 1)], "Wrong articulation cells: {:?}", choke_points.articulation_cells);
+	assert!(choke_points.bridges.len() == 4 && choke_points.bridges.contains(&((2, 1), (3, 1))), "Wrong bridges: {:?}", choke_points.bridges);
+	assert!(!choke_points.is_articulation(0, 0), "A room corner is an articulation cell");
+	let map = regions::detect_regions(&test_world);
+	let corridor = map.region_at(3, 1).unwrap().id;
+	assert!(choke_points.bridge_corridors(&map) == vec![corridor], "The corridor was not found as a bridge");
+}
+
+#[test]
+fn loop_has_no_choke_points(){
+	let mut test_world = World::new(3, 3).unwrap();
+	for x in 0..3 {
+		for y in 0..3 {
+			if (x, y) != (1, 1) {
+				test_world.put(tile::Type::Floor, x, y).unwrap();
+			}
+		}
+	}
+	test_world.put(tile::Type::VerticalWall, 1, 1).unwrap();
+	let choke_points = chokepoints::find_choke_points(&test_world);
+	assert!(choke_points.articulation_cells.len() == 0 && choke_points.bridges.len() == 0, "A ring has choke points");
+}
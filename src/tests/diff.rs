@@ -0,0 +1,36 @@
+use world::World;
+use tile;
+
+#[test]
+fn diff_apply(){
+	let mut original = World::new(8, 8).unwrap();
+	let hero = original.put(tile::Type::MainCharacter, 1, 1).unwrap();
+	let door = original.put(tile::Type::Door, 3, 3).unwrap();
+	let mut copy = original.clone();
+	let snapshot = original.snapshot();
+	original.translate(2, 0, hero).unwrap();
+	original.destroy(door).unwrap();
+	let wall = original.put(tile::Type::HorizontalWall, 5, 6).unwrap();
+	let diff = original.diff(&snapshot).unwrap();
+	assert!(diff.moved.len() == 1 && diff.moved[0].1 == (1, 1) && diff.moved[0].2 == (3, 1), "The move was not found");
+	assert!(diff.removed.len() == 1 && diff.removed[0].0.uid == door, "The destroyed door was not found");
+	assert!(diff.added.len() == 1 && diff.added[0].0.uid == wall && diff.added[0].1 == (5, 6), "The added wall was not found");
+	assert!(diff.at(3, 3).removed.len() == 1, "The change was not found at its coordinate");
+	copy.apply_diff(&diff).unwrap();
+	assert!(copy == original, "Applying the diff did not reproduce the world");
+	assert!(original.diff(&copy).unwrap().is_empty(), "Identical worlds produced a non-empty diff");
+}
+
+#[test]
+fn diff_mismatch(){
+	let mut original = World::new(4, 4).unwrap();
+	let snapshot = original.snapshot();
+	let uid = original.put(tile::Type::Floor, 0, 0).unwrap();
+	original.translate(1, 0, uid).unwrap();
+	let diff = original.diff(&snapshot).unwrap();
+	let mut other = World::new(4, 4).unwrap();
+	other.put(tile::Type::Floor, 2, 2).unwrap();
+	assert!(other.apply_diff(&diff).is_err(), "A diff was applied over a conflicting uid");
+	assert!(other.objects_at(2, 2).unwrap().len() == 1, "A failed diff changed the world");
+	assert!(World::new(5, 4).unwrap().diff(&snapshot).is_err(), "Worlds of different sizes were diffed");
+}
@@ -0,0 +1,71 @@
+use world::{
+	World,
+	WObject,
+};
+use world::designer;
+use world::pathfinding;
+use world::dijkstra::DijkstraMap;
+use world::pathfinding::Movement;
+use tile;
+
+//a world with floor in every cell
+fn floored(width: u32, height: u32) -> World {
+	let mut test_world = World::new(width, height).unwrap();
+	for x in 0..width {
+		for y in 0..height {
+			test_world.put(tile::Type::Floor, x, y).unwrap();
+		}
+	}
+	test_world
+}
+
+#[test]
+fn multi_source(){
+	let mut test_world = floored(9, 3);
+	for y in 0..2 {
+		test_world.put(tile::Type::VerticalWall, 4, y).unwrap();
+	}
+	let map = DijkstraMap::new(&test_world, &[(0, 0), (8, 0)], Movement::Four);
+	assert!(map.value(0, 0) == Some(0) && map.value(8, 0) == Some(0), "Goals are not at distance 0");
+	assert!(map.value(4, 0) == None, "A wall was given a distance");
+	assert!(map.value(3, 0) == Some(3) && map.value(5, 0) == Some(3), "Distances did not come from the nearest goal");
+	assert!(map.value(4, 2) == Some(6), "The distance around the wall is wrong");
+	assert!(map.farthest() == Some(((4, 2), 6)), "The farthest cell is wrong");
+	assert!(map.scaled(2.0).value(3, 0) == Some(6), "The map was not rescaled");
+}
+
+#[test]
+fn downhill_and_flee(){
+	let test_world = floored(5, 1);
+	let map = DijkstraMap::new(&test_world, &[(0, 0)], Movement::Four);
+	assert!(map.downhill(3, 0) == Some((2, 0)), "Downhill did not step towards the goal");
+	assert!(map.downhill(0, 0) == None, "Downhill stepped off the goal");
+	let flee = map.inverted(&test_world, 1.2);
+	assert!(flee.downhill(2, 0) == Some((3, 0)), "The flee map did not step away from the goal");
+	let mut guarded = floored(5, 1);
+	guarded.put(tile::Type::Item, 3, 0).unwrap();
+	let no_items = |objects: &[WObject]| if objects.iter().any(|o| o.obj == tile::Type::Item) { None } else { pathfinding::default_cost(objects) };
+	let costed = DijkstraMap::with_cost(&guarded, &[(0, 0)], Movement::Four, &no_items);
+	let costed_flee = costed.inverted_with_cost(&guarded, 1.2, &no_items);
+	assert!(costed_flee.value(3, 0) == None && costed_flee.value(4, 0) == None, "The flee map ignored the cost it was made with");
+	assert!(costed.inverted(&guarded, 1.2).value(3, 0).is_some(), "The default flee map did not use the default cost");
+}
+
+#[test]
+fn downhill_corners(){
+	let mut test_world = floored(3, 3);
+	test_world.put(tile::Type::VerticalWall, 1, 0).unwrap();
+	let map = DijkstraMap::new(&test_world, &[(1, 1)], Movement::Eight);
+	assert!(map.downhill(0, 0) == Some((0, 1)), "Downhill cut the corner of a wall");
+	assert!(map.downhill(2, 2) == Some((1, 1)), "Downhill did not step diagonally past open cells");
+}
+
+#[test]
+fn exit_placement(){
+	let mut test_world = World::new(6, 3).unwrap();
+	for x in 0..6 {
+		test_world.put(tile::Type::Floor, x, 1).unwrap();
+	}
+	assert!(designer::exit_position(&test_world, 1, 1) == Some((5, 1)), "The exit was not placed at the far end");
+	assert!(designer::exit_position(&test_world, 0, 0) == None, "An exit was placed from a start with no floor");
+}
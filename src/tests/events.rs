@@ -0,0 +1,58 @@
+use std::sync::{
+	Arc,
+	Mutex,
+};
+use std::thread;
+use world::World;
+use world::events::WorldEvent;
+use tile;
+
+#[test]
+fn observe_changes(){
+	let mut test_world = World::new(6, 6).unwrap();
+	let seen = Arc::new(Mutex::new(Vec::<WorldEvent>::new()));
+	let sink = seen.clone();
+	let id = test_world.subscribe(Box::new(move |event: &WorldEvent| sink.lock().unwrap().push(event.clone())));
+	let uid = test_world.put(tile::Type::MainCharacter, 1, 2).unwrap();
+	test_world.translate(1, 1, uid).unwrap();
+	test_world.destroy(uid).unwrap();
+	{
+		let events = seen.lock().unwrap();
+		assert!(events.len() == 3, "Expected 3 events, got {}", events.len());
+		assert!(events[0] == WorldEvent::Placed{uid: uid, obj: tile::Type::MainCharacter, x: 1, y: 2}, "Put was not reported");
+		assert!(events[1] == WorldEvent::Moved{uid: uid, obj: tile::Type::MainCharacter, from: (1, 2), to: (2, 3)}, "Translate was not reported");
+		assert!(events[2] == WorldEvent::Destroyed{uid: uid, obj: tile::Type::MainCharacter, x: 2, y: 3}, "Destroy was not reported");
+	}
+	assert!(test_world.translate(-5, 0, 1234).is_err(), "An unknown uid was moved");
+	assert!(seen.lock().unwrap().len() == 3, "A failed translate was reported");
+	assert!(test_world.unsubscribe(id), "The observer was not removed");
+	test_world.put(tile::Type::Floor, 0, 0).unwrap();
+	assert!(seen.lock().unwrap().len() == 3, "An unsubscribed observer was still notified");
+}
+
+#[test]
+fn observe_undo(){
+	let mut test_world = World::new(6, 6).unwrap();
+	test_world.enable_journal();
+	let uid = test_world.put(tile::Type::Door, 3, 3).unwrap();
+	let seen = Arc::new(Mutex::new(Vec::<WorldEvent>::new()));
+	let sink = seen.clone();
+	test_world.subscribe(Box::new(move |event: &WorldEvent| sink.lock().unwrap().push(event.clone())));
+	test_world.undo().unwrap();
+	assert!(seen.lock().unwrap()[0] == WorldEvent::Destroyed{uid: uid, obj: tile::Type::Door, x: 3, y: 3}, "Undoing a put was not reported as a destroy");
+	assert!(test_world.clone().put(tile::Type::Floor, 0, 0).is_ok() && seen.lock().unwrap().len() == 1, "A clone shared its observers");
+}
+
+#[test]
+fn observed_world_is_send(){
+	let mut test_world = World::new(4, 4).unwrap();
+	let seen = Arc::new(Mutex::new(0));
+	let sink = seen.clone();
+	test_world.subscribe(Box::new(move |_: &WorldEvent| *sink.lock().unwrap() += 1));
+	let worker = thread::spawn(move || {
+		test_world.put(tile::Type::Floor, 1, 1).unwrap();
+		test_world
+	});
+	let test_world = worker.join().unwrap();
+	assert!(*seen.lock().unwrap() == 1 && test_world.objects_at(1, 1).unwrap().len() == 1, "The world was not edited on another thread");
+}
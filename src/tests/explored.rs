@@ -0,0 +1,67 @@
+use std::env;
+use std::fs;
+use std::process;
+use rustc_serialize::json::{
+	ToJson,
+	Json,
+};
+use world::World;
+use world::explored::ExploredMap;
+use tile;
+
+#[test]
+fn remember_seen(){
+	let mut test_world = World::new(12, 3).unwrap();
+	let hero = test_world.put(tile::Type::MainCharacter, 1, 1).unwrap();
+	let door = test_world.put(tile::Type::Door, 3, 1).unwrap();
+	let mut explored = ExploredMap::new(hero, 12, 3);
+	explored.look(&test_world, 3, true).unwrap();
+	assert!(explored.is_visible(3, 1) && explored.is_explored(3, 1), "The door was not seen");
+	assert!(explored.remembered(3, 1).unwrap()[0].uid == door, "The door was not remembered");
+	assert!(explored.remembered(1, 1).unwrap().len() == 0, "The observer remembered itself");
+	assert!(!explored.is_explored(9, 1), "A cell out of range was explored");
+	test_world.move_to(hero, 10, 1).unwrap();
+	test_world.destroy(door).unwrap();
+	explored.look(&test_world, 3, true).unwrap();
+	assert!(!explored.is_visible(3, 1) && explored.is_explored(3, 1), "The door cell was not kept in memory");
+	assert!(explored.remembered(3, 1).unwrap().len() == 1, "The memory of the door was updated while out of view");
+}
+
+#[test]
+fn explored_json(){
+	let mut test_world = World::new(6, 6).unwrap();
+	let hero = test_world.put(tile::Type::MainCharacter, 2, 2).unwrap();
+	test_world.put(tile::Type::HorizontalWall, 2, 4).unwrap();
+	let mut explored = ExploredMap::new(hero, 6, 6);
+	explored.look(&test_world, 4, true).unwrap();
+	let json_string = explored.to_json().to_string();
+	let loaded = ExploredMap::from_json(&Json::from_str(&json_string).unwrap()).unwrap();
+	assert!(loaded.observer() == hero, "The observer was not saved");
+	assert!(loaded.remembered(2, 4) == explored.remembered(2, 4) && loaded.is_explored(0, 0) == explored.is_explored(0, 0), "The memory was not saved");
+	assert!(!loaded.is_visible(2, 2), "The visible set was saved");
+	assert!(ExploredMap::from_json(&Json::from_str("{\"observer\":1}").unwrap()).is_err(), "An incomplete explored map was loaded");
+	let outside = "{\"observer\":1,\"width\":2,\"height\":2,\"seen\":[{\"x\":X,\"y\":Y,\"objects\":[]}]}";
+	assert!(ExploredMap::from_json(&Json::from_str(&outside.replace("X", "1").replace("Y", "1")).unwrap()).is_ok(), "A cell inside the explored map was rejected");
+	assert!(ExploredMap::from_json(&Json::from_str(&outside.replace("X", "2").replace("Y", "0")).unwrap()).is_err(), "A cell past the width was loaded");
+	assert!(ExploredMap::from_json(&Json::from_str(&outside.replace("X", "0").replace("Y", "4294967297")).unwrap()).is_err(), "A cell past the height was loaded");
+}
+
+#[test]
+fn explored_with_save(){
+	let mut test_world = World::new(8, 4).unwrap();
+	let hero = test_world.put(tile::Type::MainCharacter, 1, 1).unwrap();
+	let door = test_world.put(tile::Type::Door, 4, 1).unwrap();
+	let mut explored = ExploredMap::new(hero, 8, 4);
+	explored.look(&test_world, 5, true).unwrap();
+	let path = env::temp_dir().join(format!("pgmap_{}_explored_with_save.json", process::id()));
+	test_world.save_with_explored(&path, &[explored.clone()]).unwrap();
+	let (loaded_world, loaded_explored) = World::from_file_with_explored(&path).unwrap();
+	let plain_world = World::from_file(&path).unwrap();
+	test_world.save(&path).unwrap();
+	let (_, no_explored) = World::from_file_with_explored(&path).unwrap();
+	fs::remove_file(&path).unwrap();
+	assert!(loaded_world == test_world && plain_world == test_world, "The world did not survive a save with explored maps");
+	assert!(loaded_explored.len() == 1 && loaded_explored[0].observer() == hero, "The explored map was not saved with the world");
+	assert!(loaded_explored[0].remembered(4, 1).unwrap()[0].uid == door, "The explored memory was not saved with the world");
+	assert!(no_explored.len() == 0, "A save without explored maps loaded some");
+}
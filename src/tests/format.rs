@@ -0,0 +1,95 @@
+use std::env;
+use std::fs::{
+	self,
+	File,
+};
+use std::process;
+use std::io::prelude::*;
+use rustc_serialize::json::{
+	ToJson,
+	Json,
+};
+use world::World;
+use world::format;
+use tile;
+
+//Purpose: writes a file named for this test process so parallel runs don't share it, the caller removes it
+fn write_temp(name: &str, contents: &str) -> ::std::path::PathBuf {
+	let path = env::temp_dir().join(format!("pgmap_{}_{}", process::id(), name));
+	let mut file = File::create(&path).unwrap();
+	file.write_all(contents.as_bytes()).unwrap();
+	path
+}
+
+#[test]
+fn version_two_round_trip(){
+	let mut test_world = World::new(30, 20).unwrap();
+	for x in 0..30 {
+		for y in 0..20 {
+			test_world.put(tile::Type::Floor, x, y).unwrap();
+		}
+	}
+	test_world.put(tile::Type::MainCharacter, 4, 5).unwrap();
+	let empty_cell = test_world.objects_at(7, 7).unwrap()[0].uid;
+	test_world.destroy(empty_cell).unwrap();
+	test_world.put(tile::Type::Monster, 7, 7).unwrap();
+	let json = test_world.to_json();
+	let json_string = json.to_string();
+	assert!(json.find("format_version") == Some(&Json::U64(2)), "The save does not carry its format version");
+	assert!(json_string.len() < 1000, "A uniform map was not compacted, {} bytes", json_string.len());
+	let path = write_temp("version_two.json", &json_string);
+	let load_world = World::from_file(&path).unwrap();
+	fs::remove_file(&path).unwrap();
+	assert!(load_world == test_world, "The world did not survive a save and load");
+	assert!(load_world.number_of_tiles() == 600, "The loaded world has the wrong number of tiles");
+}
+
+#[test]
+fn legacy_load(){
+	let legacy = "{\"width\":2,\"height\":1,\"current_uid\":3,\
+		\"0\":{\"0\":[{\"uid\":1,\"obj\":\"FLOOR\"},{\"uid\":2,\"obj\":\"MAIN_CHAR\"}],\"1\":[]},\
+		\"1\":{\"0\":[],\"1\":[]},\
+		\"2\":{\"0\":[],\"1\":[]}}";
+	let path = write_temp("legacy.json", legacy);
+	let load_world = World::from_file(&path).unwrap();
+	fs::remove_file(&path).unwrap();
+	assert!(load_world.number_of_tiles() == 2, "The legacy extra row and column were loaded");
+	assert!(load_world.objects_at(0, 0).unwrap().len() == 2, "The legacy objects were not loaded");
+	let bad = write_temp("future.json", "{\"format_version\":99,\"width\":1,\"height\":1,\"current_uid\":1}");
+	let result = World::from_file(&bad);
+	fs::remove_file(&bad).unwrap();
+	assert!(result.is_err(), "An unknown format version was loaded");
+}
+
+#[test]
+fn migration_chain(){
+	let legacy = Json::from_str("{\"width\":1,\"height\":1,\"current_uid\":2,\"0\":{\"0\":[{\"uid\":1,\"obj\":\"DOOR\"}]}}").unwrap();
+	let upgraded = format::upgrade(legacy).unwrap();
+	assert!(format::version_of(&upgraded).unwrap() == format::FORMAT_VERSION, "The legacy save was not upgraded to the current version");
+	let load_world = format::decode(&upgraded).unwrap();
+	assert!(load_world.where_is(1) == Some((0, 0)), "The legacy object was lost in the upgrade");
+	let current = load_world.to_json();
+	assert!(format::load(current).unwrap() == load_world, "A current save was changed by the loader");
+	let broken = Json::from_str("{\"width\":1,\"height\":1,\"current_uid\":2}").unwrap();
+	assert!(format::upgrade(broken).is_err(), "A legacy save without cells was upgraded");
+	let unversioned = Json::from_str("{\"format_version\":0}").unwrap();
+	assert!(format::load(unversioned).is_err(), "A save with format_version 0 was loaded");
+}
+
+#[test]
+fn out_of_range_numbers(){
+	let save = "{\"current_uid\":2,\"format_version\":2,\"height\":1,\"objects\":[{\"obj\":\"MAIN_CHAR\",\"uid\":1,\"x\":0,\"y\":0}],\"palette\":[],\"tile_uids\":[],\"tiles\":[[1,0]],\"width\":1}";
+	assert!(format::load(Json::from_str(save).unwrap()).is_ok(), "The valid save did not load");
+	//each of these is a value from the valid save plus 2^32, which would wrap back to it if cut down to 32 bits
+	let wrapped = [
+		("\"width\":1", "\"width\":4294967297"),
+		("\"height\":1", "\"height\":4294967297"),
+		("\"current_uid\":2", "\"current_uid\":4294967298"),
+		("\"uid\":1", "\"uid\":4294967297"),
+		("\"x\":0", "\"x\":4294967296"),
+		("\"y\":0", "\"y\":4294967296"),
+	];
+	for &(field, value) in wrapped.iter() {
+		assert!(format::load(Json::from_str(&save.replace(field, value)).unwrap()).is_err(), "A save with {} was loaded", value);
+	}
+}
@@ -0,0 +1,33 @@
+use world::World;
+use world::fov;
+use tile;
+
+#[test]
+fn open_room(){
+	let test_world = World::new(11, 11).unwrap();
+	let visible = fov::field_of_view(&test_world, (5, 5), 3, true);
+	assert!(visible.contains(&(5, 5)), "The origin is not visible");
+	assert!(visible.contains(&(8, 5)) && visible.contains(&(5, 2)) && visible.contains(&(3, 3)), "Cells within the radius are not visible");
+	assert!(!visible.contains(&(9, 5)) && !visible.contains(&(8, 8)), "Cells beyond the radius are visible");
+	let at_edge = fov::field_of_view(&test_world, (0, 0), 2, true);
+	assert!(at_edge.iter().all(|&(x, y)| x <= 2 && y <= 2), "Cells outside the world are visible");
+}
+
+#[test]
+fn walls_and_doors(){
+	let mut test_world = World::new(11, 11).unwrap();
+	for y in 0..11 {
+		if y == 5 {
+			test_world.put(tile::Type::Door, 7, y).unwrap();
+		}
+		else {
+			test_world.put(tile::Type::VerticalWall, 7, y).unwrap();
+		}
+	}
+	let closed = fov::field_of_view(&test_world, (5, 5), 5, true);
+	assert!(closed.contains(&(7, 5)) && closed.contains(&(7, 4)), "The wall and door are not lit");
+	assert!(!closed.contains(&(8, 5)) && !closed.contains(&(9, 4)), "Cells behind a closed door are visible");
+	let open = fov::field_of_view(&test_world, (5, 5), 5, false);
+	assert!(open.contains(&(8, 5)) && open.contains(&(9, 5)), "Cells behind an open door are not visible");
+	assert!(!open.contains(&(9, 1)), "Cells behind the wall are visible through the door");
+}
@@ -0,0 +1,58 @@
+use world::World;
+use tile;
+
+#[test]
+fn undo_redo(){
+	let mut test_world = World::new(10, 10).unwrap();
+	test_world.enable_journal();
+	let uid = test_world.put(tile::Type::MainCharacter, 2, 3).unwrap();
+	test_world.translate(1, 1, uid).unwrap();
+	let wall = test_world.put(tile::Type::VerticalWall, 5, 5).unwrap();
+	test_world.destroy(wall).unwrap();
+	assert!(test_world.undo().unwrap(), "Destroy was not undone");
+	assert!(test_world.where_is(wall) == Some((5, 5)), "Destroyed object was not restored");
+	assert!(test_world.undo().unwrap(), "Put was not undone");
+	assert!(test_world.where_is(wall) == None, "Put object was not removed");
+	assert!(test_world.undo().unwrap(), "Translate was not undone");
+	assert!(test_world.where_is(uid) == Some((2, 3)), "Object was not moved back");
+	assert!(test_world.redo().unwrap(), "Translate was not redone");
+	assert!(test_world.where_is(uid) == Some((3, 4)), "Object was not moved again");
+	assert!(test_world.redo().unwrap(), "Put was not redone");
+	assert!(test_world.where_is(wall) == Some((5, 5)), "Put object was not restored with the same uid");
+	assert!(test_world.put(tile::Type::Floor, 0, 0).unwrap() == wall+1, "The uid counter was not restored");
+	assert!(!test_world.redo().unwrap(), "A new edit did not clear the redo history");
+}
+
+#[test]
+fn transaction(){
+	let mut test_world = World::new(10, 10).unwrap();
+	test_world.enable_journal();
+	let first = test_world.put(tile::Type::Floor, 0, 0).unwrap();
+	test_world.begin_transaction().unwrap();
+	for x in 1..5 {
+		test_world.put(tile::Type::Floor, x, 0).unwrap();
+	}
+	test_world.commit_transaction().unwrap();
+	assert!(test_world.undo().unwrap(), "Transaction was not undone");
+	for x in 1..5 {
+		assert!(test_world.objects_at(x, 0).unwrap().len() == 0, "Transaction was only partially undone at ({},0)", x);
+	}
+	assert!(test_world.put(tile::Type::Floor, 1, 0).unwrap() == first+1, "The uid counter was not rolled back with the transaction");
+	test_world.clear_journal();
+	assert!(!test_world.undo().unwrap(), "The journal was not cleared");
+}
+
+#[test]
+fn replay(){
+	let mut test_world = World::new(6, 6).unwrap();
+	test_world.enable_journal();
+	let uid = test_world.put(tile::Type::MainCharacter, 1, 1).unwrap();
+	test_world.put(tile::Type::Door, 2, 2).unwrap();
+	test_world.move_to(uid, 4, 4).unwrap();
+	let mut replay_world = World::new(6, 6).unwrap();
+	for edit in test_world.journal().unwrap().edits().iter() {
+		replay_world.apply_edit(edit).unwrap();
+	}
+	assert!(replay_world.where_is(uid) == Some((4, 4)), "Replay did not reproduce the move");
+	assert!(replay_world.objects_at(2, 2).unwrap().len() == 1, "Replay did not reproduce the put");
+}
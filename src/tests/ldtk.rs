@@ -0,0 +1,27 @@
+use rustc_serialize::json::Json;
+use world::World;
+use world::ldtk;
+use tile;
+
+#[test]
+fn ldtk_levels(){
+	let mut test_world = World::new(2, 1).unwrap();
+	test_world.put(tile::Type::VerticalWall, 0, 0).unwrap();
+	test_world.put(tile::Type::Floor, 1, 0).unwrap();
+	test_world.put(tile::Type::Door, 1, 0).unwrap();
+	let hero = test_world.put(tile::Type::MainCharacter, 1, 0).unwrap();
+	let empty = World::new(3, 3).unwrap();
+	let project = Json::from_str(&ldtk::to_ldtk_project(&[("Start", &test_world), ("Empty", &empty)], 16).unwrap()).unwrap();
+	let levels = project.find("levels").unwrap().as_array().unwrap();
+	assert!(levels.len() == 2, "Not every world became a level");
+	assert!(levels[1].find("worldX").unwrap().as_u64() == Some(48), "The second level overlaps the first");
+	let layers = levels[0].find("layerInstances").unwrap().as_array().unwrap();
+	let csv: Vec<u64> = layers[1].find("intGridCsv").unwrap().as_array().unwrap().iter().map(|v| v.as_u64().unwrap()).collect();
+	assert!(csv == vec![2, 3], "The walls and floors were not written to the IntGrid layer");
+	let entities = layers[0].find("entityInstances").unwrap().as_array().unwrap();
+	assert!(entities.len() == 2, "The door and main character were not written as entities");
+	assert!(entities[1].find("__identifier").unwrap().as_string() == Some("MainCharacter"), "The main character has the wrong identifier");
+	assert!(entities[1].find("px").unwrap().as_array().unwrap()[0].as_u64() == Some(16), "The main character is in the wrong place");
+	let uid = entities[1].find("fieldInstances").unwrap().as_array().unwrap()[0].find("__value").unwrap().as_u64();
+	assert!(uid == Some(hero as u64), "The world uid was not kept");
+}
@@ -0,0 +1,25 @@
+use world::World;
+use world::los;
+use tile;
+
+#[test]
+fn bresenham(){
+	assert!(los::line((0, 0), (4, 2)) == vec![(0, 0), (1, 1), (2, 1), (3, 2), (4, 2)], "The shallow line is wrong");
+	assert!(los::line((3, 3), (3, 0)) == vec![(3, 3), (3, 2), (3, 1), (3, 0)], "The vertical line is wrong");
+	assert!(los::line((2, 2), (2, 2)) == vec![(2, 2)], "A line to the same cell is wrong");
+	let reversed = los::line((4, 2), (0, 0));
+	assert!(reversed[0] == (4, 2) && reversed[reversed.len()-1] == (0, 0), "A reversed line does not run from its start");
+}
+
+#[test]
+fn sight_blocked(){
+	let mut test_world = World::new(8, 3).unwrap();
+	test_world.put(tile::Type::VerticalWall, 4, 1).unwrap();
+	test_world.put(tile::Type::Door, 2, 0).unwrap();
+	assert!(!los::line_of_sight(&test_world, (0, 1), (7, 1), true), "Sight passed through a wall");
+	assert!(los::line_of_sight(&test_world, (0, 1), (4, 1), true), "The wall itself could not be seen");
+	assert!(los::cells_along_line(&test_world, (0, 1), (7, 1), true) == vec![(0, 1), (1, 1), (2, 1), (3, 1), (4, 1)], "The line did not stop at the wall");
+	assert!(!los::line_of_sight(&test_world, (0, 0), (5, 0), true), "Sight passed through a closed door");
+	assert!(los::line_of_sight(&test_world, (0, 0), (5, 0), false), "Sight did not pass through an open door");
+	assert!(!los::line_of_sight(&test_world, (0, 0), (9, 0), false), "A cell outside the world was visible");
+}
@@ -1 +1,24 @@
-pub mod world;
\ No newline at end of file
+pub mod world;
+pub mod journal;
+pub mod diff;
+pub mod events;
+pub mod blit;
+pub mod transform;
+pub mod pathfinding;
+pub mod dijkstra;
+pub mod fov;
+pub mod los;
+pub mod explored;
+pub mod regions;
+pub mod chokepoints;
+pub mod report;
+pub mod spawn;
+pub mod format;
+pub mod binary;
+pub mod streams;
+pub mod ascii;
+pub mod tiled;
+pub mod ldtk;
+pub mod rexpaint;
+pub mod render;
+pub mod svg;
\ No newline at end of file
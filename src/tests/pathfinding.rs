@@ -0,0 +1,61 @@
+use world::World;
+use world::pathfinding;
+use world::pathfinding::Movement;
+use tile;
+
+//a 7x5 world of floor with a wall down x=3 and a door at (3,4)
+fn walled_world() -> World {
+	let mut test_world = World::new(7, 5).unwrap();
+	for x in (0..7).filter(|&x| x != 3) {
+		for y in 0..5 {
+			test_world.put(tile::Type::Floor, x, y).unwrap();
+		}
+	}
+	for y in 0..4 {
+		test_world.put(tile::Type::VerticalWall, 3, y).unwrap();
+	}
+	test_world.put(tile::Type::Door, 3, 4).unwrap();
+	test_world
+}
+
+#[test]
+fn path_through_door(){
+	let test_world = walled_world();
+	let path = pathfinding::find_path(&test_world, (1, 0), (5, 0), Movement::Four).unwrap();
+	assert!(path.cells[0] == (1, 0) && path.cells[path.cells.len()-1] == (5, 0), "The path does not run from start to goal");
+	assert!(path.cells.contains(&(3, 4)), "The path did not go through the door");
+	assert!(path.cost == 12 && path.cells.len() == 13, "The path is not the shortest, cost {}", path.cost);
+	let diagonal = pathfinding::find_path(&test_world, (1, 0), (5, 0), Movement::Eight).unwrap();
+	assert!(diagonal.cost < path.cost, "Diagonal movement did not shorten the path");
+}
+
+#[test]
+fn path_blocked(){
+	let mut test_world = walled_world();
+	assert!(pathfinding::find_path(&test_world, (0, 0), (3, 1), Movement::Four).is_none(), "A path ended inside a wall");
+	let door = test_world.objects_at(3, 4).unwrap()[0].uid;
+	test_world.destroy(door).unwrap();
+	test_world.put(tile::Type::VerticalWall, 3, 4).unwrap();
+	assert!(pathfinding::find_path(&test_world, (0, 0), (6, 0), Movement::Eight).is_none(), "A path was found through a solid wall");
+}
+
+#[test]
+fn path_costs_and_limit(){
+	let test_world = walled_world();
+	let no_doors = pathfinding::find_path_with(&test_world, (1, 0), (5, 0), Movement::Four, usize::max_value(), |objects: &[::world::WObject]| {
+		if objects.iter().any(|o| o.obj == tile::Type::Door) { None } else { pathfinding::default_cost(objects) }
+	});
+	assert!(no_doors.is_none(), "A custom cost did not block the door");
+	assert!(pathfinding::find_path_with(&test_world, (1, 0), (5, 0), Movement::Four, 3, pathfinding::default_cost).is_none(), "The search limit was ignored");
+	let same = pathfinding::find_path(&test_world, (2, 2), (2, 2), Movement::Four).unwrap();
+	assert!(same.cells == vec![(2, 2)] && same.cost == 0, "A path to the start cell was not empty");
+}
+
+#[test]
+fn path_over_void(){
+	let mut test_world = World::new(5, 3).unwrap();
+	test_world.put(tile::Type::Floor, 0, 1).unwrap();
+	test_world.put(tile::Type::Floor, 4, 1).unwrap();
+	assert!(pathfinding::find_path(&test_world, (0, 1), (4, 1), Movement::Four).is_none(), "A path crossed empty cells");
+	assert!(pathfinding::find_path(&test_world, (0, 1), (4, 1), Movement::Eight).is_none(), "A diagonal path crossed empty cells");
+}
@@ -0,0 +1,42 @@
+use world::World;
+use world::regions;
+use world::regions::RegionKind;
+use tile;
+
+//two 3x3 rooms joined by a corridor with a door at the left end
+fn two_rooms() -> World {
+	let mut test_world = World::new(14, 5).unwrap();
+	for &(left, right) in [(1u32, 3u32), (9, 11)].iter() {
+		for x in left..right+1 {
+			for y in 1..4 {
+				test_world.put(tile::Type::Floor, x, y).unwrap();
+			}
+		}
+	}
+	test_world.put(tile::Type::Door, 4, 2).unwrap();
+	for x in 5..9 {
+		test_world.put(tile::Type::Floor, x, 2).unwrap();
+		test_world.put(tile::Type::HorizontalWall, x, 1).unwrap();
+		test_world.put(tile::Type::HorizontalWall, x, 3).unwrap();
+	}
+	test_world
+}
+
+#[test]
+fn detect(){
+	let test_world = two_rooms();
+	let map = regions::detect_regions(&test_world);
+	assert!(map.rooms().len() == 2, "Expected 2 rooms, found {}", map.rooms().len());
+	assert!(map.corridors().len() == 1, "Expected 1 corridor, found {}", map.corridors().len());
+	assert!(map.doors().len() == 1, "Expected 1 door, found {}", map.doors().len());
+	let first_room = map.region_at(2, 2).unwrap();
+	assert!(first_room.kind == RegionKind::Room && first_room.cells.len() == 9, "The first room was not detected whole");
+	assert!(first_room.bounds.x == 1 && first_room.bounds.y == 1 && first_room.bounds.width == 3 && first_room.bounds.height == 3, "The first room has the wrong bounds");
+	let corridor = map.region_at(6, 2).unwrap();
+	assert!(corridor.kind == RegionKind::Corridor && corridor.cells.len() == 4, "The corridor was not detected whole");
+	let door = map.region_at(4, 2).unwrap();
+	assert!(door.neighbours == vec![first_room.id, corridor.id], "The door does not join the first room to the corridor");
+	let second_room = map.region_at(10, 2).unwrap();
+	assert!(second_room.neighbours == vec![corridor.id], "The second room does not open onto the corridor");
+	assert!(map.region_at(5, 1).is_none() && map.region_at(0, 0).is_none(), "A wall or empty cell was given a region");
+}
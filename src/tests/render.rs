@@ -0,0 +1,53 @@
+extern crate flate2;
+use self::flate2::read::ZlibDecoder;
+use std::io::Read;
+use world::World;
+use world::render;
+use world::render::RenderOptions;
+use tile;
+
+//Purpose: a floor with the main character on the left and a wall on the right
+fn corridor() -> World {
+	let mut test_world = World::new(2, 1).unwrap();
+	test_world.put(tile::Type::Floor, 0, 0).unwrap();
+	test_world.put(tile::Type::MainCharacter, 0, 0).unwrap();
+	test_world.put(tile::Type::VerticalWall, 1, 0).unwrap();
+	test_world
+}
+
+#[test]
+fn render_blocks(){
+	let mut options = RenderOptions::new(2);
+	options.palette.set(tile::Type::VerticalWall, (10, 20, 30));
+	let image = render::render(&corridor(), &options).unwrap();
+	assert!(image.width == 4 && image.height == 2, "The image is the wrong size");
+	assert!(image.pixel(1, 1) == options.palette.colour_for(tile::Type::MainCharacter).unwrap(), "The main character was not drawn over the floor");
+	assert!(image.pixel(3, 0) == (10, 20, 30), "The configured wall colour was not used");
+	options.grid = Some((255, 255, 255));
+	options.overlay = Some((vec![(1, 0)].into_iter().collect(), (30, 40, 50)));
+	let image = render::render(&corridor(), &options).unwrap();
+	assert!(image.width == 5 && image.height == 3, "The grid did not close off the last cells");
+	assert!(image.pixel(2, 1) == (255, 255, 255) && image.pixel(4, 2) == (255, 255, 255), "The grid lines were not drawn");
+	assert!(image.pixel(3, 1) == (20, 30, 40), "The overlay was not blended over the wall");
+	assert!(image.to_ppm().starts_with(b"P6\n5 3\n255\n"), "The PPM header is wrong");
+	assert!(render::render(&corridor(), &RenderOptions::new(0)).is_err(), "Cells 0 pixels wide were drawn");
+	assert!(render::render(&corridor(), &RenderOptions::new(u32::max_value())).is_err(), "An image past the pixel limit was drawn");
+}
+
+#[test]
+fn png_encoding(){
+	let image = render::render(&corridor(), &RenderOptions::new(1)).unwrap();
+	let png = image.to_png();
+	assert!(png[..8] == [0x89, b'P', b'N', b'G', 0x0d, 0x0a, 0x1a, 0x0a], "The PNG signature is wrong");
+	assert!(png[12..16] == *b"IHDR" && png[16..24] == [0, 0, 0, 2, 0, 0, 0, 1], "The PNG header has the wrong size");
+	//the CRC of the header chunk, as computed by zlib
+	assert!(png[29..33] == [0x7b, 0x40, 0xe8, 0xdd], "The header chunk checksum is wrong");
+	let data_length = ((png[33] as usize) << 24) | ((png[34] as usize) << 16) | ((png[35] as usize) << 8) | png[36] as usize;
+	assert!(png[37..41] == *b"IDAT", "The image data does not follow the header");
+	let mut scanlines = Vec::new();
+	ZlibDecoder::new(&png[41..41+data_length]).read_to_end(&mut scanlines).unwrap();
+	let mut expected = vec![0];
+	expected.extend_from_slice(&image.pixels);
+	assert!(scanlines == expected, "The image data does not decompress to the pixels");
+	assert!(png.ends_with(&[0, 0, 0, 0, b'I', b'E', b'N', b'D', 0xae, 0x42, 0x60, 0x82]), "The PNG does not end with IEND");
+}
@@ -0,0 +1,90 @@
+use world::World;
+use world::designer;
+use world::pathfinding;
+use world::pathfinding::Movement;
+use world::regions::Bounds;
+use world::report::{
+	GenerationReport,
+	RoomShape,
+};
+use tile;
+
+#[test]
+fn room_report(){
+	let mut test_world = World::new(12, 10).unwrap();
+	let bounds = Bounds{x: 3, y: 2, width: 6, height: 5};
+	for x in bounds.x..bounds.x+bounds.width {
+		for y in bounds.y..bounds.y+bounds.height {
+			if y == bounds.y || y == bounds.y+bounds.height-1 {
+				test_world.put(tile::Type::HorizontalWall, x, y).unwrap();
+			}
+			else if x == bounds.x || x == bounds.x+bounds.width-1 {
+				test_world.put(tile::Type::VerticalWall, x, y).unwrap();
+			}
+			else {
+				test_world.put(tile::Type::Floor, x, y).unwrap();
+			}
+		}
+	}
+	let door = test_world.put(tile::Type::Door, 8, 4).unwrap();
+	let mut report = GenerationReport::new();
+	let id = report.add_room(RoomShape::Rectangle, bounds);
+	report.add_door(door, 8, 4);
+	let room = &report.rooms[id];
+	assert!(room.center == (6, 4), "The room center is wrong");
+	assert!(room.bounds.contains(room.center.0, room.center.1), "The room center is outside the room");
+	assert!(test_world.objects_at(room.center.0, room.center.1).unwrap()[0].obj == tile::Type::Floor, "The room center is not on the floor");
+	assert!(report.room_at(bounds.x, bounds.y).unwrap().id == id, "The room was not found by its corner");
+	assert!(report.room_at(bounds.x+bounds.width, bounds.y).is_none(), "A cell outside the room was reported as inside it");
+	assert!(report.doors.len() == 1 && report.doors[0].uid == door, "The door was not recorded");
+}
+
+#[test]
+fn connections(){
+	let mut report = GenerationReport::new();
+	let first = report.add_room(RoomShape::Rectangle, Bounds{x: 0, y: 0, width: 5, height: 5});
+	let second = report.add_room(RoomShape::Diamond, Bounds{x: 10, y: 0, width: 5, height: 5});
+	let third = report.add_room(RoomShape::Rectangle, Bounds{x: 20, y: 0, width: 5, height: 5});
+	report.connect(second, first);
+	report.connect(first, second);
+	report.connect(second, third);
+	assert!(report.connections == vec![(first, second), (second, third)], "Connections were not recorded once each");
+	assert!(report.connected_rooms(second) == vec![first, third], "The connected rooms are wrong");
+	assert!(report.rooms[second].center == (12, 2), "The diamond center is wrong");
+}
+
+#[test]
+fn dungeon_report(){
+	let mut test_world = World::new(80, 60).unwrap();
+	let report = designer::create_dungeon(&mut test_world, &[6]);
+	assert!(report.rooms.len() > 1, "Only {} rooms were built", report.rooms.len());
+	for room in report.rooms.iter() {
+		for x in room.bounds.x+1..room.bounds.x+room.bounds.width-1 {
+			for y in room.bounds.y+1..room.bounds.y+room.bounds.height-1 {
+				assert!(test_world.objects_at(x, y).unwrap().iter().map(|o| o.obj).collect::<Vec<_>>() == vec![tile::Type::Floor], "Room {} has no floor at ({},{})", room.id, x, y);
+			}
+		}
+		let path = pathfinding::find_path(&test_world, report.rooms[0].center, room.center, Movement::Four);
+		assert!(path.is_some(), "Room {} can't be reached from the first room", room.id);
+	}
+	assert!(report.connections.len() == report.rooms.len()-1, "Every room after the first should have been connected once");
+	assert!(report.corridors.len() == report.connections.len() && report.doors.len() == 2*report.connections.len(), "Corridors and doors were not recorded");
+	for door in report.doors.iter() {
+		assert!(test_world.objects_at(door.x, door.y).unwrap().iter().any(|o| o.uid == door.uid && o.obj == tile::Type::Door), "The door at ({},{}) was not placed", door.x, door.y);
+	}
+	for corridor in report.corridors.iter() {
+		assert!(test_world.is_walkable(corridor.start.0, corridor.start.1) && test_world.is_walkable(corridor.end.0, corridor.end.1), "Corridor {} is closed off", corridor.id);
+	}
+}
+
+#[test]
+fn dungeon_fits_world(){
+	for seed in 0..20 {
+		let mut test_world = World::new(10, 8).unwrap();
+		let report = designer::create_dungeon(&mut test_world, &[seed]);
+		for room in report.rooms.iter() {
+			assert!(room.bounds.x+room.bounds.width <= 10 && room.bounds.y+room.bounds.height <= 8, "Room {} with seed {} is past the edge", room.id, seed);
+		}
+	}
+	assert!(designer::create_dungeon(&mut World::new(2, 2).unwrap(), &[1]).rooms.len() == 0, "A room was built in a world too small for one");
+}
@@ -0,0 +1,67 @@
+use std::env;
+use std::fs::{
+	self,
+	File,
+};
+use std::io::Write;
+use std::process;
+use world::World;
+use world::rexpaint;
+use world::rexpaint::XpTable;
+use tile;
+
+#[test]
+fn xp_round_trip(){
+	let mut test_world = World::new(3, 1).unwrap();
+	test_world.put(tile::Type::VerticalWall, 0, 0).unwrap();
+	test_world.put(tile::Type::Floor, 1, 0).unwrap();
+	test_world.put(tile::Type::MainCharacter, 1, 0).unwrap();
+	test_world.put(tile::Type::Floor, 2, 0).unwrap();
+	test_world.put(tile::Type::Door, 2, 0).unwrap();
+	let table = XpTable::standard();
+	let bytes = rexpaint::to_xp(&test_world, &table).unwrap();
+	assert!(bytes.len() == 8+2*(8+3*10), "The image does not have two layers of three cells");
+	assert!(bytes[16] == 186 && bytes[26] == 250, "The terrain was not drawn with CP437 glyphs");
+	assert!(bytes[61..64] == [255, 0, 255], "An empty cell on the entity layer is not transparent");
+	let path = env::temp_dir().join(format!("pgmap_{}_xp_round_trip.xp", process::id()));
+	rexpaint::save_xp(&test_world, &path, &table).unwrap();
+	let loaded = rexpaint::load_xp(&path, &table).unwrap();
+	fs::remove_file(&path).unwrap();
+	assert!(rexpaint::to_xp(&loaded, &table).unwrap() == bytes, "The world did not load back the same");
+	let mut lone_door = World::new(1, 1).unwrap();
+	lone_door.put(tile::Type::Door, 0, 0).unwrap();
+	let door_bytes = rexpaint::to_xp(&lone_door, &table).unwrap();
+	assert!(door_bytes[16] == 43, "A door without floor was not drawn as the door glyph");
+	let door_stack: Vec<tile::Type> = rexpaint::from_xp(&door_bytes, &table).unwrap().objects_at(0, 0).unwrap().iter().map(|o| o.obj).collect();
+	assert!(door_stack == vec![tile::Type::Floor, tile::Type::Door], "The door glyph did not load as a door on floor");
+}
+
+#[test]
+fn xp_unknown_glyphs(){
+	let mut table = XpTable::new();
+	table.set(35, vec![tile::Type::HorizontalWall], (255, 255, 255));
+	let mut test_world = World::new(2, 1).unwrap();
+	test_world.put(tile::Type::HorizontalWall, 0, 0).unwrap();
+	let mut bytes = rexpaint::to_xp(&test_world, &table).unwrap();
+	assert!(rexpaint::from_xp(&bytes, &table).unwrap() == test_world, "A blank cell was not left empty");
+	bytes[16] = 36;
+	assert!(rexpaint::from_xp(&bytes, &table).is_err(), "An unknown glyph was loaded");
+	let mut huge = bytes.clone();
+	huge[8..16].copy_from_slice(&[0xff, 0xff, 0xff, 0x7f, 0xff, 0xff, 0xff, 0x7f]);
+	assert!(rexpaint::from_xp(&huge, &table).is_err(), "A layer too big for the image was loaded");
+	huge[11] = 0xff;
+	assert!(rexpaint::from_xp(&huge, &table).is_err(), "A layer with a negative width was loaded");
+	let mut wide = vec![0u8; 16+5000*10];
+	wide[..16].copy_from_slice(&[0xff, 0xff, 0xff, 0xff, 1, 0, 0, 0, 0x88, 0x13, 0, 0, 1, 0, 0, 0]);
+	assert!(rexpaint::from_xp(&wide, &table).err().map_or(false, |e| e.contains("limit")), "A layer wider than a world can be was loaded");
+	let mut layered = bytes.clone();
+	layered[4] = 10;
+	assert!(rexpaint::from_xp(&layered, &table).is_err(), "An image with more layers than REXPaint allows was loaded");
+	test_world.put(tile::Type::Floor, 1, 0).unwrap();
+	assert!(rexpaint::to_xp(&test_world, &table).is_err(), "A stack without a glyph was written");
+	let path = env::temp_dir().join(format!("pgmap_{}_xp_not_gzip.xp", process::id()));
+	File::create(&path).unwrap().write_all(b"not gzip").unwrap();
+	let result = rexpaint::load_xp(&path, &table);
+	fs::remove_file(&path).unwrap();
+	assert!(result.is_err(), "A file that isn't gzip was loaded");
+}
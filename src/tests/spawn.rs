@@ -0,0 +1,56 @@
+use world::World;
+use world::spawn;
+use world::spawn::{
+	SpawnTable,
+	SpawnRules,
+};
+use world::regions;
+use tile;
+
+//two 4x4 rooms of floor joined by a one wide corridor along y=2, with the main character in the first room
+fn spawn_world() -> (World, u32) {
+	let mut test_world = World::new(16, 6).unwrap();
+	for &left in [0u32, 12].iter() {
+		for x in left..left+4 {
+			for y in 1..5 {
+				test_world.put(tile::Type::Floor, x, y).unwrap();
+			}
+		}
+	}
+	for x in 4..12 {
+		test_world.put(tile::Type::Floor, x, 2).unwrap();
+	}
+	let hero = test_world.put(tile::Type::MainCharacter, 1, 1).unwrap();
+	(test_world, hero)
+}
+
+#[test]
+fn spawn_constraints(){
+	let (mut test_world, _) = spawn_world();
+	let mut table = SpawnTable::new();
+	table.add(tile::Type::Monster, 3, 1, 5);
+	table.add(tile::Type::Item, 1, 0, 10);
+	let rules = SpawnRules{count: 20, min_distance_from_player: 5, max_per_room: 3, allow_corridors: false};
+	let uids = spawn::spawn(&mut test_world, &table, 2, &rules, &[7, 7, 7]).unwrap();
+	assert!(uids.len() == 3, "Expected the one far room to be filled to 3, got {}", uids.len());
+	let map = regions::detect_regions(&test_world);
+	for uid in uids.iter() {
+		let (x, y) = test_world.where_is(*uid).unwrap();
+		assert!(x >= 12, "A spawn at ({},{}) is too close to the main character or in a corridor", x, y);
+		assert!(map.region_at(x, y).unwrap().kind == regions::RegionKind::Room, "A spawn at ({},{}) is not in a room", x, y);
+	}
+}
+
+#[test]
+fn spawn_reproducible(){
+	let mut table = SpawnTable::new();
+	table.add(tile::Type::Monster, 1, 0, 0);
+	table.add(tile::Type::Item, 1, 0, 0);
+	let rules = SpawnRules{count: 6, min_distance_from_player: 0, max_per_room: 100, allow_corridors: true};
+	let (mut first, _) = spawn_world();
+	let (mut second, _) = spawn_world();
+	spawn::spawn(&mut first, &table, 0, &rules, &[1, 2, 3]).unwrap();
+	spawn::spawn(&mut second, &table, 0, &rules, &[1, 2, 3]).unwrap();
+	assert!(first == second, "The same seed spawned different things");
+	assert!(spawn::spawn(&mut first, &table, 4, &rules, &[1, 2, 3]).is_err(), "Spawned with no entries at the difficulty");
+}
@@ -0,0 +1,36 @@
+use std::env;
+use std::fs;
+use std::io::Cursor;
+use std::process;
+use world::World;
+use tile;
+
+#[test]
+fn memory_buffers(){
+	let mut test_world = World::new(6, 4).unwrap();
+	test_world.put(tile::Type::Door, 2, 3).unwrap();
+	let mut json_buffer = Vec::new();
+	test_world.write_to(&mut json_buffer).unwrap();
+	assert!(World::read_from(&mut Cursor::new(json_buffer)).unwrap() == test_world, "The json save did not survive an in-memory buffer");
+	let mut binary_buffer = Vec::new();
+	test_world.write_binary_to(&mut binary_buffer).unwrap();
+	assert!(World::read_from(&mut Cursor::new(binary_buffer)).unwrap() == test_world, "The binary save was not detected in a stream");
+	assert!(World::from_json_str(&test_world.to_json_string()).unwrap() == test_world, "The json string did not round trip");
+	assert!(World::read_from(&mut Cursor::new(vec![0xffu8, 0xfe])).is_err(), "Garbage was loaded");
+}
+
+#[test]
+fn save_creates_file(){
+	let path = env::temp_dir().join(format!("pgmap_{}_save_creates_file.json", process::id()));
+	let mut test_world = World::new(3, 3).unwrap();
+	test_world.put(tile::Type::Floor, 1, 1).unwrap();
+	test_world.save(&path).unwrap();
+	let loaded = World::from_file(&path).unwrap();
+	fs::remove_file(&path).unwrap();
+	assert!(loaded == test_world, "A world saved to a new file did not load back");
+	let binary_path = env::temp_dir().join(format!("pgmap_{}_save_creates_file.bin", process::id()));
+	test_world.save_binary(&binary_path).unwrap();
+	let loaded_binary = World::from_file(&binary_path).unwrap();
+	fs::remove_file(&binary_path).unwrap();
+	assert!(loaded_binary == test_world, "A binary save did not load back from a file");
+}
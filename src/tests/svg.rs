@@ -0,0 +1,42 @@
+use world::World;
+use world::svg;
+use world::regions::Bounds;
+use world::report::{
+	GenerationReport,
+	RoomShape,
+};
+use tile;
+
+#[test]
+fn svg_layout(){
+	let mut test_world = World::new(4, 3).unwrap();
+	for x in 0..4 {
+		test_world.put(tile::Type::HorizontalWall, x, 0).unwrap();
+	}
+	test_world.put(tile::Type::VerticalWall, 0, 1).unwrap();
+	test_world.put(tile::Type::VerticalWall, 0, 2).unwrap();
+	for x in 1..4 {
+		test_world.put(tile::Type::Floor, x, 1).unwrap();
+	}
+	test_world.put(tile::Type::Door, 3, 1).unwrap();
+	test_world.put(tile::Type::MainCharacter, 1, 1).unwrap();
+	let plain = svg::to_svg(&test_world, 10, None);
+	assert!(plain.starts_with("<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"40\" height=\"30\""), "The drawing is the wrong size");
+	assert!(plain.contains("<line x1=\"5\" y1=\"5\" x2=\"35\" y2=\"5\"/>"), "The horizontal walls were not merged into one line");
+	assert!(plain.contains("<line x1=\"5\" y1=\"15\" x2=\"5\" y2=\"25\"/>"), "The vertical walls were not merged into one line");
+	assert!(plain.matches("<line").count() == 2, "A wall was drawn more than once");
+	assert!(plain.contains("<rect x=\"10\" y=\"10\" width=\"30\" height=\"10\"/>"), "The floor was not drawn as one rectangle");
+	assert!(plain.contains("class=\"door\"") && plain.contains("class=\"hero\""), "The door and main character were not drawn");
+	assert!(!plain.contains("<text"), "Rooms were labelled without a report");
+	let mut report = GenerationReport::new();
+	report.add_room(RoomShape::Rectangle, Bounds{x: 0, y: 0, width: 4, height: 3});
+	let labelled = svg::to_svg(&test_world, 10, Some(&report));
+	assert!(labelled.contains("<text x=\"25\" y=\"15\">Room 0</text>"), "The room was not labelled at its center");
+	let mut wide = World::new(3, 1).unwrap();
+	for x in 0..3 {
+		wide.put(tile::Type::Floor, x, 0).unwrap();
+	}
+	let huge = svg::to_svg(&wide, 1 << 31, None);
+	assert!(huge.contains("width=\"6442450944\" height=\"2147483648\""), "A drawing wider than u32 wrapped around");
+	assert!(huge.contains("<rect x=\"0\" y=\"0\" width=\"6442450944\" height=\"2147483648\"/>"), "A floor run wider than u32 wrapped around");
+}
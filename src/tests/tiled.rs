@@ -0,0 +1,66 @@
+use world::World;
+use world::tiled;
+use world::tiled::TileIdMap;
+use tile;
+
+//Purpose: a small room with a door, the hero and a monster
+fn room() -> World {
+	let mut test_world = World::new(3, 2).unwrap();
+	for x in 0..3 {
+		test_world.put(tile::Type::HorizontalWall, x, 0).unwrap();
+		test_world.put(tile::Type::Floor, x, 1).unwrap();
+	}
+	test_world.put(tile::Type::Door, 2, 1).unwrap();
+	test_world.put(tile::Type::MainCharacter, 0, 1).unwrap();
+	test_world.put(tile::Type::Monster, 1, 1).unwrap();
+	test_world
+}
+
+//Purpose: the types in every cell, bottom to top, so worlds can be compared without their uids
+fn stacks(test_world: &World) -> Vec<Vec<tile::Type>> {
+	let mut cells = Vec::new();
+	for y in 0..test_world.height() {
+		for x in 0..test_world.width() {
+			cells.push(test_world.objects_at(x, y).unwrap().iter().map(|o| o.obj).collect());
+		}
+	}
+	cells
+}
+
+#[test]
+fn tmx_round_trip(){
+	let ids = TileIdMap::standard();
+	let xml = tiled::to_tmx(&room(), &ids, 16, 16).unwrap();
+	assert!(xml.contains("name=\"layer1\""), "The door was not put on a second tile layer");
+	assert!(xml.contains("type=\"MAIN_CHAR\" x=\"0\" y=\"16\""), "The main character was not written to the object layer");
+	let loaded = tiled::from_tmx(&xml, &ids).unwrap();
+	assert!(stacks(&loaded) == stacks(&room()), "The map did not load back the same");
+	assert!(loaded.width() == 3 && loaded.height() == 2, "The map has the wrong size");
+	let door = loaded.objects_at(2, 1).unwrap();
+	assert!(door.len() == 2 && door[1].obj == tile::Type::Door, "The door was not stacked on the floor");
+	let hero = loaded.objects_at(0, 1).unwrap();
+	assert!(hero.len() == 2 && hero[1].obj == tile::Type::MainCharacter, "The main character was not put back on the floor");
+	assert!(tiled::from_tmx(&xml.replace("encoding=\"csv\"", "encoding=\"base64\""), &ids).is_err(), "A base64 layer was accepted");
+	assert!(tiled::from_tmx(&xml.replace("tilewidth=\"16\"", "tilewidth=\"0\""), &ids).is_err(), "A map with zero width tiles was loaded");
+	assert!(tiled::to_tmx(&room(), &ids, 0, 16).is_err(), "A map with zero width tiles was exported");
+	let mut far = World::new(3, 1).unwrap();
+	far.put(tile::Type::MainCharacter, 2, 0).unwrap();
+	assert!(tiled::to_tmx(&far, &ids, 1 << 31, 16).is_err(), "An object past the largest pixel position was exported");
+}
+
+#[test]
+fn tmj_round_trip(){
+	let mut ids = TileIdMap::standard();
+	ids.set(10, tile::Type::Floor).unwrap();
+	assert!(ids.set(0, tile::Type::Item).is_err() && ids.id_for(tile::Type::Item).is_none(), "Tile id 0 was accepted");
+	assert!(ids.type_for(3).is_none() && ids.id_for(tile::Type::Floor) == Some(10), "Remapping the floor kept its old id");
+	let text = tiled::to_tmj(&room(), &ids, 8, 8).unwrap();
+	let loaded = tiled::from_tmj(&text, &ids).unwrap();
+	assert!(tiled::from_tmj(&text.replace("\"tileheight\":8", "\"tileheight\":0"), &ids).is_err(), "A map with zero height tiles was loaded");
+	assert!(stacks(&loaded) == stacks(&room()), "The map did not load back the same");
+	assert!(loaded.objects_at(1, 1).unwrap()[1].obj == tile::Type::Monster, "The monster was not loaded");
+	assert!(tiled::from_tmj(&text, &TileIdMap::standard()).is_err(), "An unmapped tile id was loaded");
+	let mut no_doors = TileIdMap::standard();
+	no_doors.set(4, tile::Type::Floor).unwrap();
+	assert!(tiled::to_tmx(&room(), &no_doors, 8, 8).is_err(), "A door without a tile id was exported");
+}
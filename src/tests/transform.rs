@@ -0,0 +1,38 @@
+use world::World;
+use world::transform::{
+	Rotation,
+	Mirror,
+};
+use tile;
+
+#[test]
+fn rotate(){
+	let mut test_world = World::new(4, 3).unwrap();
+	let wall = test_world.put(tile::Type::HorizontalWall, 1, 0).unwrap();
+	let hero = test_world.put(tile::Type::MainCharacter, 3, 2).unwrap();
+	let quarter = test_world.rotate(Rotation::Quarter);
+	assert!(quarter.width() == 3 && quarter.height() == 4, "A quarter turn did not swap the dimensions");
+	assert!(quarter.where_is(wall) == Some((2, 1)), "The wall was not rotated into place");
+	assert!(quarter.objects_at(2, 1).unwrap()[0].obj == tile::Type::VerticalWall, "A quarter turn did not swap the wall direction");
+	assert!(quarter.where_is(hero) == Some((0, 3)), "The character was not rotated into place");
+	let half = test_world.rotate(Rotation::Half);
+	assert!(half.where_is(hero) == Some((0, 0)) && half.objects_at(2, 2).unwrap()[0].obj == tile::Type::HorizontalWall, "A half turn was not applied correctly");
+	let full = quarter.rotate(Rotation::ThreeQuarter);
+	assert!(full == test_world, "A quarter turn and a three quarter turn did not cancel out");
+}
+
+#[test]
+fn mirror_crop_pad(){
+	let mut test_world = World::new(4, 3).unwrap();
+	let hero = test_world.put(tile::Type::MainCharacter, 0, 1).unwrap();
+	assert!(test_world.mirror(Mirror::Horizontal).where_is(hero) == Some((3, 1)), "The horizontal mirror was not applied");
+	assert!(test_world.mirror(Mirror::Vertical).where_is(hero) == Some((0, 1)), "The vertical mirror moved the middle row");
+	let padded = test_world.pad(2, 1, 3, 0).unwrap();
+	assert!(padded.width() == 7 && padded.height() == 6 && padded.number_of_tiles() == 42, "Padding produced the wrong size");
+	assert!(padded.where_is(hero) == Some((2, 4)), "Padding did not shift the character");
+	let cropped = padded.crop(2, 3, 4, 3).unwrap();
+	assert!(cropped == test_world, "Cropping the padding back off did not restore the world");
+	assert!(test_world.pad(u32::max_value(), 1, 0, 0).is_err(), "Padding past u32 wrapped around");
+	assert!(test_world.pad(0, 0, 4096, 0).is_err(), "Padding past the largest side was allowed");
+	assert!(test_world.pad(2000, 2000, 1000, 1000).is_err(), "Padding past the largest number of cells was allowed");
+}
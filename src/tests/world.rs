@@ -1,7 +1,13 @@
-use world::World;
+use world::{
+	World,
+	MoveError,
+};
 use world::designer;
 use tile;
+use std::env;
+use std::fs;
 use std::path::Path;
+use std::process;
 #[test]
 fn empty_creation(){
 	let width = 4;
@@ -44,9 +50,11 @@ fn save_load(){
 		Ok(_)	=>	(),
 		Err(x)	=>	panic!("World could not put test object, reason: {}", x),
 	};
-    let save_path = Path::new("test_file");
-	save_world.save(save_path).unwrap();
-	let load_world = World::from_file(save_path).unwrap();
+	//named for this test process so the committed test_file and parallel runs are left alone
+	let save_path = env::temp_dir().join(format!("pgmap_{}_save_load.json", process::id()));
+	save_world.save(&save_path).unwrap();
+	let load_world = World::from_file(&save_path).unwrap();
+	fs::remove_file(&save_path).unwrap();
 	let mut z_level = load_world.objects_at(x,y).unwrap();
 	assert!(load_world.width() == width, "World width was not saved correctly");
 	assert!(load_world.height() == height, "World height was not saved correctly");
@@ -54,6 +62,16 @@ fn save_load(){
 
 }
 
+#[test]
+fn load_legacy_file(){
+	//test_file is a save from before format versions, it is only ever read
+	let load_world = World::from_file(Path::new("test_file")).unwrap();
+	assert!(load_world.width() == 20 && load_world.height() == 20, "The legacy save has the wrong size");
+	let z_level = load_world.objects_at(4, 2).unwrap();
+	assert!(z_level.len() == 1 && z_level[0].obj == tile::Type::MainCharacter, "The legacy save did not have its object");
+	assert!(load_world.objects_at(5, 2).unwrap().len() == 0, "The legacy save has objects it shouldn't");
+}
+
 #[test]
 fn move_destroy(){
 	let test_object = tile::Type::MainCharacter;
@@ -89,3 +107,30 @@ fn room(){
 		}
 	}
 }*/
+
+#[test]
+fn translate_bounds(){
+	let test_object = tile::Type::MainCharacter;
+	let mut test_world = World::new(5, 5).unwrap();
+	let uid = test_world.put(test_object, 0, 2).unwrap();
+	assert!(test_world.translate(-1, 0, uid) == Err(MoveError::OutOfBounds(-1, 2)), "Object was moved off the left edge");
+	assert!(test_world.translate(0, 3, uid) == Err(MoveError::OutOfBounds(0, 5)), "Object was moved off the bottom edge");
+	assert!(test_world.where_is(uid) == Some((0, 2)), "Object was removed by a failed translate");
+	assert!(test_world.translate(1, 0, 9999) == Err(MoveError::NoSuchObject(9999)), "Translate accepted an unknown uid");
+	test_world.move_to(uid, 4, 4).unwrap();
+	assert!(test_world.where_is(uid) == Some((4, 4)), "Object was not moved to the absolute position");
+}
+
+#[test]
+fn translate_collision(){
+	let mut test_world = World::new(5, 5).unwrap();
+	let uid = test_world.put(tile::Type::MainCharacter, 1, 1).unwrap();
+	test_world.put(tile::Type::VerticalWall, 2, 1).unwrap();
+	test_world.translate(1, 0, uid).unwrap();
+	test_world.move_to(uid, 1, 1).unwrap();
+	test_world.set_collision(true);
+	assert!(test_world.translate(1, 0, uid) == Err(MoveError::Blocked(2, 1)), "Object was moved into a wall");
+	assert!(test_world.where_is(uid) == Some((1, 1)), "Object was removed by a blocked translate");
+	test_world.put(tile::Type::Door, 1, 2).unwrap();
+	test_world.translate(0, 1, uid).unwrap();
+}
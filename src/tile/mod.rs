@@ -1,8 +1,105 @@
-#[derive(Clone, PartialEq, Copy)]
+#[derive(Clone, PartialEq, Copy, Debug)]
 pub enum Type {
     HorizontalWall,
     VerticalWall,
     Floor,
     MainCharacter,
     Door,
+    Monster,
+    Item,
+}
+
+impl Type {
+    //Purpose: the name used for this type in save files
+    pub fn name(&self) -> &'static str {
+        match *self {
+            Type::HorizontalWall    =>  "H_WALL",
+            Type::VerticalWall      =>  "V_WALL",
+            Type::Floor             =>  "FLOOR",
+            Type::MainCharacter     =>  "MAIN_CHAR",
+            Type::Door              =>  "DOOR",
+            Type::Monster           =>  "MONSTER",
+            Type::Item              =>  "ITEM",
+        }
+    }
+
+    //Purpose: the type a save file name stands for, None if the name is unknown
+    pub fn from_name(name: &str) -> Option<Type> {
+        match name {
+            "H_WALL"    =>  Some(Type::HorizontalWall),
+            "V_WALL"    =>  Some(Type::VerticalWall),
+            "FLOOR"     =>  Some(Type::Floor),
+            "MAIN_CHAR" =>  Some(Type::MainCharacter),
+            "DOOR"      =>  Some(Type::Door),
+            "MONSTER"   =>  Some(Type::Monster),
+            "ITEM"      =>  Some(Type::Item),
+            _           =>  None,
+        }
+    }
+
+    //Purpose: the number used for this type in binary saves, these never change once released
+    pub fn code(&self) -> u8 {
+        match *self {
+            Type::HorizontalWall    =>  0,
+            Type::VerticalWall      =>  1,
+            Type::Floor             =>  2,
+            Type::MainCharacter     =>  3,
+            Type::Door              =>  4,
+            Type::Monster           =>  5,
+            Type::Item              =>  6,
+        }
+    }
+
+    //Purpose: the type a binary save number stands for, None if the number is unknown
+    pub fn from_code(code: u8) -> Option<Type> {
+        match code {
+            0   =>  Some(Type::HorizontalWall),
+            1   =>  Some(Type::VerticalWall),
+            2   =>  Some(Type::Floor),
+            3   =>  Some(Type::MainCharacter),
+            4   =>  Some(Type::Door),
+            5   =>  Some(Type::Monster),
+            6   =>  Some(Type::Item),
+            _   =>  None,
+        }
+    }
+
+    //Purpose: whether an object of this type can share a cell with something moving into it
+    pub fn is_passable(&self) -> bool {
+        match *self {
+            Type::HorizontalWall    =>  false,
+            Type::VerticalWall      =>  false,
+            Type::Floor             =>  true,
+            Type::MainCharacter     =>  true,
+            Type::Door              =>  true,
+            Type::Monster           =>  true,
+            Type::Item              =>  true,
+        }
+    }
+
+    //Purpose: whether an object of this type stops sight, doors are treated as closed
+    pub fn is_opaque(&self) -> bool {
+        match *self {
+            Type::HorizontalWall    =>  true,
+            Type::VerticalWall      =>  true,
+            Type::Floor             =>  false,
+            Type::MainCharacter     =>  false,
+            Type::Door              =>  true,
+            Type::Monster           =>  false,
+            Type::Item              =>  false,
+        }
+    }
+
+    //Purpose: whether this type is part of the map itself rather than something standing on it
+    pub fn is_terrain(&self) -> bool {
+        match *self {
+            Type::HorizontalWall    =>  true,
+            Type::VerticalWall      =>  true,
+            Type::Floor             =>  true,
+            Type::MainCharacter     =>  false,
+            Type::Door              =>  true,
+            Type::Monster           =>  false,
+            Type::Item              =>  false,
+        }
+    }
 }
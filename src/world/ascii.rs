@@ -0,0 +1,109 @@
+use world::World;
+use tile::Type;
+
+//Which character stands for which stack of objects, listed bottom to top
+#[derive(Clone, PartialEq, Debug)]
+pub struct GlyphTable {
+    entries: Vec<(char, Vec<Type>)>,
+    //written for cells no entry matches
+    pub unknown: char,
+}
+
+impl GlyphTable {
+    //Purpose: a table with no glyphs in it
+    pub fn new() -> GlyphTable {
+        GlyphTable{entries: Vec::new(), unknown: '?'}
+    }
+
+    /*
+    Signature:  standard()
+    Purpose:    The glyphs used when nothing else is configured
+    Inputs:     None
+    Outputs:    A table with space for an empty cell, - and | for walls, . for floor, + for a door,
+                and @, M and ! for the main character, a monster and an item standing on floor
+    */
+    pub fn standard() -> GlyphTable {
+        let mut table = GlyphTable::new();
+        table.set(' ', vec![]);
+        table.set('-', vec![Type::HorizontalWall]);
+        table.set('|', vec![Type::VerticalWall]);
+        table.set('.', vec![Type::Floor]);
+        table.set('+', vec![Type::Door]);
+        table.set('@', vec![Type::Floor, Type::MainCharacter]);
+        table.set('M', vec![Type::Floor, Type::Monster]);
+        table.set('!', vec![Type::Floor, Type::Item]);
+        table
+    }
+
+    //Purpose: makes a glyph stand for a stack, replacing what it stood for before, earlier glyphs win when dumping a stack two glyphs share
+    pub fn set(&mut self, glyph: char, stack: Vec<Type>) {
+        match self.entries.iter().position(|e| e.0 == glyph) {
+            Some(index) =>  self.entries[index].1 = stack,
+            None        =>  self.entries.push((glyph, stack)),
+        };
+    }
+
+    pub fn stack_for(&self, glyph: char) -> Option<&Vec<Type>> {
+        self.entries.iter().find(|e| e.0 == glyph).map(|e| &e.1)
+    }
+
+    //Purpose: the glyph for a stack, falling back to the glyph of its top object alone
+    pub fn glyph_for(&self, stack: &[Type]) -> char {
+        match self.entries.iter().find(|e| e.1.as_slice() == stack) {
+            Some(entry) =>  return entry.0,
+            None        =>  (),
+        };
+        match stack.last() {
+            Some(top)   =>  match self.entries.iter().find(|e| e.1.len() == 1 && e.1[0] == *top) {
+                Some(entry) =>  entry.0,
+                None        =>  self.unknown,
+            },
+            None        =>  self.unknown,
+        }
+    }
+}
+
+/*
+Signature:  from_ascii(&str,&GlyphTable)
+Purpose:    Creation of a new world object from a text map, one character per cell
+Inputs:     The text, with one line per row, and the glyph table, the world is as wide as the longest line and short lines are padded with empty cells
+Outputs:    The world with each stack put in reading order, a String naming the first character the table doesn't know
+*/
+pub fn from_ascii(text: &str, glyphs: &GlyphTable) -> Result<World, String> {
+    let lines: Vec<&str> = text.lines().collect();
+    let width = lines.iter().map(|l| l.chars().count()).max().unwrap_or(0);
+    let mut world = match World::new(width as u32, lines.len() as u32) {
+        Ok(val) =>  val,
+        Err(e)  =>  return Err(e),
+    };
+    for (y, line) in lines.iter().enumerate() {
+        for (x, glyph) in line.chars().enumerate() {
+            let stack = match glyphs.stack_for(glyph) {
+                Some(val)   =>  val,
+                None        =>  return Err(format!("The character '{}' on line {} column {} is not in the glyph table", glyph, y+1, x+1)),
+            };
+            for obj in stack.iter() {
+                world.put(*obj, x as u32, y as u32).unwrap();
+            }
+        }
+    }
+    Ok(world)
+}
+
+/*
+Signature:  to_ascii(&World,&GlyphTable)
+Purpose:    Dump a world as a text map, one character per cell
+Inputs:     The world and the glyph table
+Outputs:    One line per row, each ending in a newline, cells with no matching glyph are written as the table's unknown glyph
+*/
+pub fn to_ascii(world: &World, glyphs: &GlyphTable) -> String {
+    let mut text = String::new();
+    for y in 0..world.height() {
+        for x in 0..world.width() {
+            let stack: Vec<Type> = world.objects_at(x, y).unwrap_or(Vec::new()).iter().map(|o| o.obj).collect();
+            text.push(glyphs.glyph_for(&stack));
+        }
+        text.push('\n');
+    }
+    text
+}
@@ -0,0 +1,198 @@
+use world::{
+    World,
+    WObject,
+};
+use world::format::Layout;
+use tile::Type;
+
+pub const MAGIC: &'static [u8] = b"PGMB";
+pub const BINARY_VERSION: u16 = 1;
+
+/*
+Layout of a binary save, every number is little endian and the sections mirror format::Layout:
+    magic           the four bytes PGMB
+    version         u16
+    width, height, current_uid      u32 each
+    palette         u8 count followed by a u8 type code per entry
+    tile runs       u32 count followed by (u32 run length, u8 palette index + 1) per run
+    tile uid runs   u32 count followed by (u32 first uid, u32 run length) per run
+    objects         u32 count followed by (u32 uid, u8 type code, u32 x, u32 y) per object
+*/
+
+fn push_u16(bytes: &mut Vec<u8>, value: u16) {
+    bytes.push(value as u8);
+    bytes.push((value >> 8) as u8);
+}
+
+fn push_u32(bytes: &mut Vec<u8>, value: u32) {
+    for shift in [0, 8, 16, 24].iter() {
+        bytes.push((value >> *shift) as u8);
+    }
+}
+
+//Reads numbers off the front of a byte slice, failing instead of running off the end
+struct Reader<'a> {
+    bytes: &'a [u8],
+    position: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn take(&mut self, count: usize, what: &str) -> Result<&'a [u8], String> {
+        if self.position+count > self.bytes.len() {
+            return Err(format!("The binary save ends in the middle of the {}", what));
+        }
+        let slice = &self.bytes[self.position..self.position+count];
+        self.position = self.position+count;
+        Ok(slice)
+    }
+
+    //Purpose: fails unless count entries of size bytes each fit in what is left, so a bad count can't start a huge loop
+    fn fits(&self, count: u32, size: u64, what: &str) -> Result<(), String> {
+        if count as u64*size > (self.bytes.len()-self.position) as u64 {
+            return Err(format!("The binary save claims {} {} but is too short to hold them", count, what));
+        }
+        Ok(())
+    }
+
+    fn u8(&mut self, what: &str) -> Result<u8, String> {
+        self.take(1, what).map(|b| b[0])
+    }
+
+    fn u16(&mut self, what: &str) -> Result<u16, String> {
+        self.take(2, what).map(|b| b[0] as u16 | (b[1] as u16) << 8)
+    }
+
+    fn u32(&mut self, what: &str) -> Result<u32, String> {
+        self.take(4, what).map(|b| b[0] as u32 | (b[1] as u32) << 8 | (b[2] as u32) << 16 | (b[3] as u32) << 24)
+    }
+
+    fn obj(&mut self, what: &str) -> Result<Type, String> {
+        match self.u8(what) {
+            Ok(code)    =>  match Type::from_code(code) {
+                Some(val)   =>  Ok(val),
+                None        =>  Err(format!("The binary save has the unknown type code {} in the {}", code, what)),
+            },
+            Err(e)      =>  Err(e),
+        }
+    }
+}
+
+impl World {
+    /*
+    Signature:  to_bytes()
+    Purpose:    Write the world in the compact binary save format
+    Inputs:     None
+    Outputs:    The bytes of the save, reading them back with from_bytes gives the same world as a json save would
+    */
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let layout = Layout::of(self);
+        let mut bytes = Vec::new();
+        bytes.extend_from_slice(MAGIC);
+        push_u16(&mut bytes, BINARY_VERSION);
+        push_u32(&mut bytes, layout.width);
+        push_u32(&mut bytes, layout.height);
+        push_u32(&mut bytes, layout.current_uid);
+        bytes.push(layout.palette.len() as u8);
+        for obj in layout.palette.iter() {
+            bytes.push(obj.code());
+        }
+        push_u32(&mut bytes, layout.tile_runs.len() as u32);
+        for &(length, index) in layout.tile_runs.iter() {
+            push_u32(&mut bytes, length as u32);
+            bytes.push(index as u8);
+        }
+        push_u32(&mut bytes, layout.uid_runs.len() as u32);
+        for &(first, length) in layout.uid_runs.iter() {
+            push_u32(&mut bytes, first as u32);
+            push_u32(&mut bytes, length as u32);
+        }
+        push_u32(&mut bytes, layout.objects.len() as u32);
+        for &(object, x, y) in layout.objects.iter() {
+            push_u32(&mut bytes, object.uid);
+            bytes.push(object.obj.code());
+            push_u32(&mut bytes, x);
+            push_u32(&mut bytes, y);
+        }
+        bytes
+    }
+
+    /*
+    Signature:  from_bytes(&[u8])
+    Purpose:    Creation of a new world object from a binary save
+    Inputs:     The bytes written by to_bytes
+    Outputs:    The world if the save is valid, a String describing what is wrong with it if not
+    */
+    pub fn from_bytes(bytes: &[u8]) -> Result<World, String> {
+        match read_layout(bytes) {
+            Ok(layout)  =>  match layout.build() {
+                Ok(world)   =>  Ok(world),
+                Err(e)      =>  Err(format!("The binary save {}", e)),
+            },
+            Err(e)      =>  Err(e),
+        }
+    }
+}
+
+//Purpose: whether a buffer starts like a binary save
+pub fn is_binary(bytes: &[u8]) -> bool {
+    bytes.starts_with(MAGIC)
+}
+
+fn read_layout(bytes: &[u8]) -> Result<Layout, String> {
+    if !is_binary(bytes) {
+        return Err("The data is not a binary save, the magic bytes are missing".to_string());
+    }
+    let mut reader = Reader{bytes: bytes, position: MAGIC.len()};
+    let version = match reader.u16("header") { Ok(val) => val, Err(e) => return Err(e) };
+    if version != BINARY_VERSION {
+        return Err(format!("The binary save has the unsupported version {}, the newest this release reads is {}", version, BINARY_VERSION));
+    }
+    let width = match reader.u32("header") { Ok(val) => val, Err(e) => return Err(e) };
+    let height = match reader.u32("header") { Ok(val) => val, Err(e) => return Err(e) };
+    let current_uid = match reader.u32("header") { Ok(val) => val, Err(e) => return Err(e) };
+    let mut layout = Layout{width: width, height: height, current_uid: current_uid, palette: Vec::new(), tile_runs: Vec::new(), uid_runs: Vec::new(), objects: Vec::new()};
+
+    let palette_count = match reader.u8("palette") { Ok(val) => val, Err(e) => return Err(e) };
+    for _ in 0..palette_count {
+        match reader.obj("palette") {
+            Ok(val) =>  layout.palette.push(val),
+            Err(e)  =>  return Err(e),
+        };
+    }
+    let run_count = match reader.u32("tile runs") { Ok(val) => val, Err(e) => return Err(e) };
+    match reader.fits(run_count, 5, "tile runs") {
+        Ok(_)   =>  (),
+        Err(e)  =>  return Err(e),
+    };
+    for _ in 0..run_count {
+        let length = match reader.u32("tile runs") { Ok(val) => val, Err(e) => return Err(e) };
+        let index = match reader.u8("tile runs") { Ok(val) => val, Err(e) => return Err(e) };
+        layout.tile_runs.push((length as u64, index as u64));
+    }
+    let uid_run_count = match reader.u32("tile uid runs") { Ok(val) => val, Err(e) => return Err(e) };
+    match reader.fits(uid_run_count, 8, "tile uid runs") {
+        Ok(_)   =>  (),
+        Err(e)  =>  return Err(e),
+    };
+    for _ in 0..uid_run_count {
+        let first = match reader.u32("tile uid runs") { Ok(val) => val, Err(e) => return Err(e) };
+        let length = match reader.u32("tile uid runs") { Ok(val) => val, Err(e) => return Err(e) };
+        layout.uid_runs.push((first as u64, length as u64));
+    }
+    let object_count = match reader.u32("objects") { Ok(val) => val, Err(e) => return Err(e) };
+    match reader.fits(object_count, 13, "objects") {
+        Ok(_)   =>  (),
+        Err(e)  =>  return Err(e),
+    };
+    for _ in 0..object_count {
+        let uid = match reader.u32("objects") { Ok(val) => val, Err(e) => return Err(e) };
+        let obj = match reader.obj("objects") { Ok(val) => val, Err(e) => return Err(e) };
+        let x = match reader.u32("objects") { Ok(val) => val, Err(e) => return Err(e) };
+        let y = match reader.u32("objects") { Ok(val) => val, Err(e) => return Err(e) };
+        layout.objects.push((WObject{uid: uid, obj: obj}, x, y));
+    }
+    if reader.position != bytes.len() {
+        return Err(format!("The binary save has {} bytes left over after the objects", bytes.len()-reader.position));
+    }
+    Ok(layout)
+}
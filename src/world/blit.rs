@@ -0,0 +1,93 @@
+use std::collections::HashMap;
+use world::World;
+
+//How a pasted world treats cells that already hold objects
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum BlitMode {
+    //destroy whatever is in the cell before pasting, empty source cells leave the destination alone
+    Overwrite,
+    //paste on top of whatever is in the cell
+    Merge,
+    //leave the cell alone if it holds anything
+    SkipOccupied,
+}
+
+impl World {
+    /*
+    Signature:  extract(u32,u32,u32,u32)
+    Purpose:    Copy a rectangular region of the world into a new world
+    Inputs:     The x and y coordinates of the upper left corner followed by the width and height of the region
+    Outputs:    A world the size of the region holding copies of its objects with their uids unchanged, a String if the region is not inside the world
+    */
+    pub fn extract(&self, x: u32, y: u32, width: u32, height: u32) -> Result<World, String> {
+        if x as u64+width as u64 > self.w as u64 || y as u64+height as u64 > self.h as u64 {
+            return Err(format!("The region at ({},{}) of size {}x{} does not fit in a {}x{} world", x, y, width, height, self.w, self.h));
+        }
+        let mut data = HashMap::new();
+        for region_x in 0..width {
+            for region_y in 0..height {
+                let z_level = match self.data.get(&(x+region_x, y+region_y)) {
+                    Some(val)   =>  val.clone(),
+                    None        =>  return Err(format!("Could not retrieve Vector at ({},{})", x+region_x, y+region_y)),
+                };
+                data.insert((region_x, region_y), z_level);
+            }
+        }
+        Ok(World::from_parts(width, height, self.current_uid, data))
+    }
+
+    /*
+    Signature:  blit(&World,u32,u32,BlitMode)
+    Purpose:    Paste another world into this one, giving every pasted object a new uid from this world
+    Inputs:     The world to paste, the x and y offset of its upper left corner and how to treat occupied cells
+    Outputs:    A map from each pasted object's uid in the source to its new uid, a String if the source does not fit
+    */
+    pub fn blit(&mut self, source: &World, x_offset: u32, y_offset: u32, mode: BlitMode) -> Result<HashMap<u32,u32>, String> {
+        if x_offset as u64+source.w as u64 > self.w as u64 || y_offset as u64+source.h as u64 > self.h as u64 {
+            return Err(format!("A {}x{} world does not fit at ({},{}) in a {}x{} world", source.w, source.h, x_offset, y_offset, self.w, self.h));
+        }
+        //group the whole paste into one undo step unless the caller already opened one
+        let own_transaction = match self.journal {
+            Some(ref journal)   =>  !journal.in_transaction(),
+            None                =>  false,
+        };
+        if own_transaction {
+            self.begin_transaction().unwrap();
+        }
+        let mut uid_map = HashMap::new();
+        for source_x in 0..source.w {
+            for source_y in 0..source.h {
+                let source_objects = match source.data.get(&(source_x, source_y)) {
+                    Some(val)   =>  val,
+                    None        =>  continue,
+                };
+                if source_objects.len() == 0 {
+                    continue;
+                }
+                let (x, y) = (x_offset+source_x, y_offset+source_y);
+                let existing = self.objects_at(x, y).unwrap();
+                match mode {
+                    BlitMode::Overwrite     =>  {
+                        for object in existing.iter() {
+                            self.destroy(object.uid).unwrap();
+                        }
+                    },
+                    BlitMode::Merge         =>  (),
+                    BlitMode::SkipOccupied  =>  {
+                        if existing.len() != 0 {
+                            continue;
+                        }
+                    },
+                };
+                for object in source_objects.iter() {
+                    let new_uid = self.put(object.obj, x, y).unwrap();
+                    uid_map.insert(object.uid, new_uid);
+                }
+            }
+        }
+        if own_transaction {
+            self.commit_transaction().unwrap();
+        }
+        Ok(uid_map)
+    }
+}
@@ -0,0 +1,128 @@
+use std::collections::{
+    BTreeSet,
+    HashMap,
+};
+use world::World;
+use world::regions::{
+    orthogonal,
+    RegionMap,
+    RegionKind,
+};
+
+//The weak spots of the walkable part of a world
+#[derive(Clone, PartialEq, Debug)]
+pub struct ChokePoints {
+    //cells whose removal splits the walkable area they are in, sorted
+    pub articulation_cells: Vec<(u32,u32)>,
+    //pairs of neighbouring cells that are the only link between two parts of the walkable area, sorted
+    pub bridges: Vec<((u32,u32),(u32,u32))>,
+}
+
+impl ChokePoints {
+    //Purpose: the ids of the corridors that hold an articulation cell, the ones that are the only way between their ends
+    pub fn bridge_corridors(&self, regions: &RegionMap) -> Vec<usize> {
+        let mut ids = BTreeSet::new();
+        for &(x, y) in self.articulation_cells.iter() {
+            match regions.region_at(x, y) {
+                Some(region) if region.kind == RegionKind::Corridor =>  { ids.insert(region.id); },
+                _                                                   =>  (),
+            };
+        }
+        ids.into_iter().collect()
+    }
+
+    pub fn is_articulation(&self, x: u32, y: u32) -> bool {
+        self.articulation_cells.binary_search(&(x,y)).is_ok()
+    }
+}
+
+fn walkable_neighbours(world: &World, cell: (u32,u32)) -> Vec<(u32,u32)> {
+    orthogonal(world, cell.0, cell.1).into_iter().filter(|&(x, y)| world.is_walkable(x, y)).collect()
+}
+
+/*
+Signature:  find_choke_points(&World)
+Purpose:    Find the articulation cells and bridges of the graph of walkable cells joined to their orthogonal neighbours
+Inputs:     The world
+Outputs:    The choke points, found with an iterative Tarjan search so large maps can't overflow the stack
+*/
+pub fn find_choke_points(world: &World) -> ChokePoints {
+    let mut discovered = HashMap::<(u32,u32), u32>::new();
+    let mut low = HashMap::<(u32,u32), u32>::new();
+    let mut articulation = BTreeSet::new();
+    let mut bridges = Vec::new();
+    let mut time = 0u32;
+    for root_x in 0..world.width() {
+        for root_y in 0..world.height() {
+            let root = (root_x, root_y);
+            if !world.is_walkable(root_x, root_y) || discovered.contains_key(&root) {
+                continue;
+            }
+            discovered.insert(root, time);
+            low.insert(root, time);
+            time = time+1;
+            let mut root_children = 0;
+            //each entry is a cell, the cell it was reached from, its walkable neighbours and how many have been looked at
+            let mut stack = vec![(root, None, walkable_neighbours(world, root), 0usize)];
+            loop {
+                let next = match stack.last_mut() {
+                    Some(&mut (cell, parent, ref neighbours, ref mut index)) =>  {
+                        if *index < neighbours.len() {
+                            *index = *index+1;
+                            Some((cell, parent, neighbours[*index-1]))
+                        }
+                        else {
+                            None
+                        }
+                    },
+                    None    =>  break,
+                };
+                match next {
+                    Some((cell, parent, neighbour)) =>  {
+                        if Some(neighbour) == parent {
+                            continue;
+                        }
+                        match discovered.get(&neighbour) {
+                            Some(&neighbour_time)   =>  {
+                                if neighbour_time < low[&cell] {
+                                    low.insert(cell, neighbour_time);
+                                }
+                            },
+                            None                    =>  {
+                                discovered.insert(neighbour, time);
+                                low.insert(neighbour, time);
+                                time = time+1;
+                                if cell == root {
+                                    root_children = root_children+1;
+                                }
+                                stack.push((neighbour, Some(cell), walkable_neighbours(world, neighbour), 0));
+                            },
+                        };
+                    },
+                    None                            =>  {
+                        let (cell, parent, _, _) = stack.pop().unwrap();
+                        let parent = match parent {
+                            Some(val)   =>  val,
+                            None        =>  continue,
+                        };
+                        if low[&cell] < low[&parent] {
+                            let cell_low = low[&cell];
+                            low.insert(parent, cell_low);
+                        }
+                        if parent != root && low[&cell] >= discovered[&parent] {
+                            articulation.insert(parent);
+                        }
+                        if low[&cell] > discovered[&parent] {
+                            bridges.push(if parent < cell { (parent, cell) } else { (cell, parent) });
+                        }
+                    },
+                };
+            }
+            if root_children > 1 {
+                articulation.insert(root);
+            }
+        }
+    }
+    bridges.sort();
+    ChokePoints{articulation_cells: articulation.into_iter().collect(), bridges: bridges}
+}
@@ -5,15 +5,23 @@ use self::rand::distributions::{
 };
 use self::rand::SeedableRng;
 use world::World;
+use world::dijkstra::DijkstraMap;
+use world::regions::Bounds;
+use world::report::{
+    GenerationReport,
+    RoomShape,
+};
+use world::pathfinding::Movement;
 use tile::Type;
 
-enum Direction{
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum Direction{
     Horizontal,
     Vertical,
 }
 
 //Purpose: creates a horizontal corridor from left to right
-fn create_horizontal_corridor(world: &mut World, starting_x: u32, starting_y: u32, length: u32) -> Result<(),String>{
+fn create_horizontal_corridor(world: &mut World, report: &mut GenerationReport, starting_x: u32, starting_y: u32, length: u32) -> Result<usize,String>{
     if starting_x+length>world.w{
         return Err(format!("out of bounds at ({},{}) on the x-axis", starting_x+length, starting_y))
     }
@@ -44,11 +52,11 @@ fn create_horizontal_corridor(world: &mut World, starting_x: u32, starting_y: u3
         world.put(Type::HorizontalWall,x,starting_y-1).unwrap();
         world.put(Type::HorizontalWall,x,starting_y+1).unwrap();
     } 
-    Ok(())
+    Ok(report.add_corridor((starting_x,starting_y), (starting_x+length,starting_y), Direction::Horizontal))
 }
 
 //Purpose: Creates a vertical corridor starting from the top down
-fn create_vertical_corridor(world: &mut World, starting_x: u32, starting_y: u32, length: u32) -> Result<(),String>{
+fn create_vertical_corridor(world: &mut World, report: &mut GenerationReport, starting_x: u32, starting_y: u32, length: u32) -> Result<usize,String>{
     if starting_y+length > world.h{
         return Err(format!("The cooridinates ({},{}) are out of bounds on the y-axis", starting_x, starting_y+length))
     }
@@ -80,20 +88,26 @@ fn create_vertical_corridor(world: &mut World, starting_x: u32, starting_y: u32,
         world.put(Type::VerticalWall, starting_x-1,y).unwrap();
         world.put(Type::VerticalWall, starting_x+1,y).unwrap();
     }
-    Ok(())
+    Ok(report.add_corridor((starting_x,starting_y), (starting_x,starting_y+length), Direction::Vertical))
 }
 
-fn create_corridor(world: &mut World, starting_x: u32, starting_y: u32, length: u32, direction: Direction) -> Result<(),String>{
+fn create_corridor(world: &mut World, report: &mut GenerationReport, starting_x: u32, starting_y: u32, length: u32, direction: Direction) -> Result<usize,String>{
     match direction{
-        Direction::Horizontal   =>  create_horizontal_corridor(world,starting_x,starting_y,length),
-        Direction::Vertical     =>  create_vertical_corridor(world,starting_x,starting_y,length),
+        Direction::Horizontal   =>  create_horizontal_corridor(world,report,starting_x,starting_y,length),
+        Direction::Vertical     =>  create_vertical_corridor(world,report,starting_x,starting_y,length),
     }
 }
 
-fn create_rectangle_room(world: &mut World, upper_left_x: u32, upper_left_y: u32, height: u32, width: u32) -> Result<(), String>{
+fn create_rectangle_room(world: &mut World, report: &mut GenerationReport, upper_left_x: u32, upper_left_y: u32, height: u32, width: u32) -> Result<usize, String>{
+    if upper_left_x as u64+width as u64 > world.w as u64 || upper_left_y as u64+height as u64 > world.h as u64{
+        return Err(format!("The rectangle room at ({},{}) is out of bounds", upper_left_x, upper_left_y))
+    }
     for x in upper_left_x..upper_left_x+width{
         for y in upper_left_y..upper_left_y+height{
-            let objects = world.objects_at(x,y).unwrap();
+            let objects = match world.objects_at(x,y){
+                Some(x) =>  x,
+                None    =>  return Err(format!("Could not retrieve Vector at ({},{})", x, y))
+            };
             if objects.len() != 0{
                 return Err(format!("Overlap at ({},{})",x,y));
             }
@@ -104,7 +118,7 @@ fn create_rectangle_room(world: &mut World, upper_left_x: u32, upper_left_y: u32
             if x == upper_left_x || x==upper_left_x+width-1{
                 world.put(Type::HorizontalWall,x,y).unwrap();
             }
-            else if y == upper_left_y || y == upper_left_y+width-1{
+            else if y == upper_left_y || y == upper_left_y+height-1{
                 world.put(Type::VerticalWall,x,y).unwrap();
             }
             else{
@@ -112,10 +126,10 @@ fn create_rectangle_room(world: &mut World, upper_left_x: u32, upper_left_y: u32
             }
         }
     }
-    Ok(())
+    Ok(report.add_room(RoomShape::Rectangle, Bounds{x: upper_left_x, y: upper_left_y, width: width, height: height}))
 }
 
-fn create_diamond_room(world: &mut World, center_x: u32, center_y: u32, radius: u32) -> Result<(), String>{
+fn create_diamond_room(world: &mut World, report: &mut GenerationReport, center_x: u32, center_y: u32, radius: u32) -> Result<usize, String>{
     if center_x+radius>world.w || center_y+radius>world.h{
         return Err(format!("The diamond room was out of bounds"))
     }
@@ -147,17 +161,142 @@ fn create_diamond_room(world: &mut World, center_x: u32, center_y: u32, radius:
     world.put(Type::VerticalWall,center_x+radius,center_y).unwrap();
     world.put(Type::HorizontalWall,center_x,center_y-radius).unwrap();
     world.put(Type::HorizontalWall,center_x,center_y+radius).unwrap();
-    Ok(())
+    Ok(report.add_room(RoomShape::Diamond, Bounds{x: center_x-radius, y: center_y-radius, width: 2*radius+1, height: 2*radius+1}))
+}
+
+//Purpose: finds the walkable cell farthest from the start, where the exit should go
+pub fn exit_position(world: &World, start_x: u32, start_y: u32) -> Option<(u32,u32)> {
+    let distances = DijkstraMap::new(world, &[(start_x, start_y)], Movement::Four);
+    match distances.farthest() {
+        Some((position, distance)) if distance > 0  =>  Some(position),
+        _                                           =>  None,
+    }
+}
+
+//Purpose: whether every cell of a rectangle is in the world and empty
+fn is_clear(world: &World, bounds: Bounds) -> bool {
+    (bounds.x..bounds.x+bounds.width).all(|x| (bounds.y..bounds.y+bounds.height).all(|y| match world.objects_at(x, y) {
+        Some(objects)   =>  objects.len() == 0,
+        None            =>  false,
+    }))
+}
+
+//Purpose: empties a cell and stacks new objects in it, returning the uid of the top one
+fn replace_cell(world: &mut World, x: u32, y: u32, stack: &[Type]) -> Result<u32, String> {
+    let objects = match world.objects_at(x, y) {
+        Some(val)   =>  val,
+        None        =>  return Err(format!("Could not retrieve Vector at ({},{})", x, y)),
+    };
+    for object in objects.iter() {
+        match world.destroy(object.uid) {
+            Ok(_)   =>  (),
+            Err(e)  =>  return Err(e),
+        };
+    }
+    let mut top = 0;
+    for obj in stack.iter() {
+        top = match world.put(*obj, x, y) {
+            Ok(uid) =>  uid,
+            Err(e)  =>  return Err(e),
+        };
+    }
+    Ok(top)
 }
 
-pub fn create_dungeon(world: &mut World, seed: &[usize]){
+/*
+Signature:  attach_room(&mut World,&mut GenerationReport,&mut StdRng,usize,Direction)
+Purpose:    Build a rectangle room to the right of or below a room already in the report, joined to it by a corridor with a door at each end
+Inputs:     The world, the report, the random number generator, the id of the room to build from and which way to build
+Outputs:    The id of the new room, a String if there is no space for the room or its corridor, in which case nothing is built
+*/
+fn attach_room(world: &mut World, report: &mut GenerationReport, rng: &mut rand::StdRng, from: usize, direction: Direction) -> Result<usize, String> {
+    let size_range = Range::new(3u32, 20);
+    let length_range = Range::new(1u32, 8);
+    let from_bounds = report.rooms[from].bounds;
+    let (width, height, length) = (size_range.ind_sample(rng), size_range.ind_sample(rng), length_range.ind_sample(rng));
+    //the door in the old room's wall, the first corridor cell, the door in the new room's wall and the new room
+    let (near_door, start, far_door, room) = match direction {
+        Direction::Horizontal   =>  {
+            let y = from_bounds.y+Range::new(1, from_bounds.height-1).ind_sample(rng);
+            let start = (from_bounds.x+from_bounds.width, y);
+            let far_door = (start.0+length+1, y);
+            let room_y = match y.checked_sub(Range::new(1, height-1).ind_sample(rng)) {
+                Some(val)   =>  val,
+                None        =>  return Err(format!("The room off room {} would start above the world", from)),
+            };
+            ((start.0-1, y), start, far_door, Bounds{x: far_door.0, y: room_y, width: width, height: height})
+        },
+        Direction::Vertical     =>  {
+            let x = from_bounds.x+Range::new(1, from_bounds.width-1).ind_sample(rng);
+            let start = (x, from_bounds.y+from_bounds.height);
+            let far_door = (x, start.1+length+1);
+            let room_x = match x.checked_sub(Range::new(1, width-1).ind_sample(rng)) {
+                Some(val)   =>  val,
+                None        =>  return Err(format!("The room off room {} would start left of the world", from)),
+            };
+            ((x, start.1-1), start, far_door, Bounds{x: room_x, y: far_door.1, width: width, height: height})
+        },
+    };
+    let corridor = match direction {
+        Direction::Horizontal   =>  Bounds{x: start.0, y: start.1-1, width: length+1, height: 3},
+        Direction::Vertical     =>  Bounds{x: start.0-1, y: start.1, width: 3, height: length+1},
+    };
+    if !is_clear(world, corridor) || !is_clear(world, room) {
+        return Err(format!("There is no space for a room off room {}", from));
+    }
+    let room_id = match create_rectangle_room(world, report, room.x, room.y, room.height, room.width) {
+        Ok(val) =>  val,
+        Err(e)  =>  return Err(e),
+    };
+    match create_corridor(world, report, start.0, start.1, length, direction) {
+        Ok(_)   =>  (),
+        Err(e)  =>  return Err(e),
+    };
+    let end = match direction {
+        Direction::Horizontal   =>  (start.0+length, start.1),
+        Direction::Vertical     =>  (start.0, start.1+length),
+    };
+    //the corridor is built with walls across both ends, they become floor so it opens onto the doors
+    for &(x, y) in [start, end].iter() {
+        match replace_cell(world, x, y, &[Type::Floor]) {
+            Ok(_)   =>  (),
+            Err(e)  =>  return Err(e),
+        };
+    }
+    for &(x, y) in [near_door, far_door].iter() {
+        match replace_cell(world, x, y, &[Type::Floor, Type::Door]) {
+            Ok(uid) =>  report.add_door(uid, x, y),
+            Err(e)  =>  return Err(e),
+        };
+    }
+    report.connect(from, room_id);
+    Ok(room_id)
+}
+
+//Purpose: fills the world with rectangle rooms joined by corridors and reports the rooms, corridors and doors it built
+pub fn create_dungeon(world: &mut World, seed: &[usize]) -> GenerationReport{
+    let mut report = GenerationReport::new();
+    //the smallest room is 3x3
+    if world.w < 3 || world.h < 3 {
+        return report;
+    }
     let room_generation_range = Range::new(1u32,100);
-    let height_width_range = Range::new(1u32, 20);
-    let starting_room_x_range = Range::new(1u32, world.w);
-    let starting_room_y_range = Range::new(1u32, world.h);
+    let height_width_range = Range::new(3u32, 20);
+    let starting_room_x_range = Range::new(0u32, world.w);
+    let starting_room_y_range = Range::new(0u32, world.h);
+    let direction_range = Range::new(0u32, 2);
     let mut rng = rand::StdRng::from_seed(seed);
-    let mut current_x = starting_room_x_range.ind_sample(&mut rng);
-    let mut current_y = starting_room_y_range.ind_sample(&mut rng);
-    create_rectangle_room(world, current_x, current_y, height_width_range.ind_sample(&mut rng), height_width_range.ind_sample(&mut rng)).unwrap();
-
+    for _ in 0..room_generation_range.ind_sample(&mut rng) {
+        //rooms that don't fit are skipped
+        if report.rooms.len() == 0 {
+            let (current_x, current_y) = (starting_room_x_range.ind_sample(&mut rng), starting_room_y_range.ind_sample(&mut rng));
+            let _ = create_rectangle_room(world, &mut report, current_x, current_y, height_width_range.ind_sample(&mut rng), height_width_range.ind_sample(&mut rng));
+        }
+        else {
+            let from = Range::new(0, report.rooms.len()).ind_sample(&mut rng);
+            let direction = if direction_range.ind_sample(&mut rng) == 0 { Direction::Horizontal } else { Direction::Vertical };
+            let _ = attach_room(world, &mut report, &mut rng, from, direction);
+        }
+    }
+    report
 }
@@ -0,0 +1,148 @@
+use std::collections::{
+    HashMap,
+    BTreeSet,
+};
+use world::{
+    World,
+    WObject,
+};
+use world::journal::Edit;
+
+//The objects that differ between two worlds, each list is sorted by uid
+#[derive(Clone, PartialEq, Debug)]
+pub struct WorldDiff {
+    pub added: Vec<(WObject, (u32,u32))>,
+    pub removed: Vec<(WObject, (u32,u32))>,
+    pub moved: Vec<(WObject, (u32,u32), (u32,u32))>,
+    pub current_uid: u32,
+}
+
+impl WorldDiff {
+    pub fn is_empty(&self) -> bool {
+        self.added.len() == 0 && self.removed.len() == 0 && self.moved.len() == 0
+    }
+
+    //Purpose: every coordinate that gained or lost an object
+    pub fn cells(&self) -> BTreeSet<(u32,u32)> {
+        let mut ret_set = BTreeSet::new();
+        for &(_, coordinates) in self.added.iter() {
+            ret_set.insert(coordinates);
+        }
+        for &(_, coordinates) in self.removed.iter() {
+            ret_set.insert(coordinates);
+        }
+        for &(_, from, to) in self.moved.iter() {
+            ret_set.insert(from);
+            ret_set.insert(to);
+        }
+        ret_set
+    }
+
+    //Purpose: the objects that were added, removed, moved in or moved out at a single coordinate
+    pub fn at(&self, x: u32, y: u32) -> WorldDiff {
+        WorldDiff{
+            added: self.added.iter().filter(|a| a.1 == (x,y)).cloned().collect(),
+            removed: self.removed.iter().filter(|r| r.1 == (x,y)).cloned().collect(),
+            moved: self.moved.iter().filter(|m| m.1 == (x,y) || m.2 == (x,y)).cloned().collect(),
+            current_uid: self.current_uid,
+        }
+    }
+}
+
+//Purpose: indexes every object in the world by uid
+fn locate_all(world: &World) -> HashMap<u32, (WObject, (u32,u32))> {
+    let mut ret_map = HashMap::new();
+    for (coordinates, z_level) in world.data.iter() {
+        for object in z_level.iter() {
+            ret_map.insert(object.uid, (*object, *coordinates));
+        }
+    }
+    ret_map
+}
+
+impl World {
+    /*
+    Signature:  diff(&World)
+    Purpose:    Work out what changed in this world since a snapshot was taken
+    Inputs:     The earlier snapshot, which must be the same size
+    Outputs:    A WorldDiff that turns the snapshot into this world, a String if the sizes differ
+    */
+    pub fn diff(&self, before: &World) -> Result<WorldDiff, String> {
+        if self.w != before.w || self.h != before.h {
+            return Err(format!("Cannot diff a {}x{} world against a {}x{} world", self.w, self.h, before.w, before.h));
+        }
+        let old_objects = locate_all(before);
+        let new_objects = locate_all(self);
+        let mut diff = WorldDiff{added: Vec::new(), removed: Vec::new(), moved: Vec::new(), current_uid: self.current_uid};
+        for (uid, &(object, coordinates)) in new_objects.iter() {
+            match old_objects.get(uid) {
+                Some(&(old_object, _)) if old_object != object  =>  diff.added.push((object, coordinates)),
+                Some(&(_, old_coordinates)) if old_coordinates != coordinates   =>  diff.moved.push((object, old_coordinates, coordinates)),
+                Some(_) =>  (),
+                None    =>  diff.added.push((object, coordinates)),
+            };
+        }
+        for (uid, &(object, coordinates)) in old_objects.iter() {
+            match new_objects.get(uid) {
+                Some(&(new_object, _)) if new_object == object  =>  (),
+                _   =>  diff.removed.push((object, coordinates)),
+            };
+        }
+        diff.added.sort_by(|a, b| a.0.uid.cmp(&b.0.uid));
+        diff.removed.sort_by(|a, b| a.0.uid.cmp(&b.0.uid));
+        diff.moved.sort_by(|a, b| a.0.uid.cmp(&b.0.uid));
+        Ok(diff)
+    }
+
+    /*
+    Signature:  apply_diff(&WorldDiff)
+    Purpose:    Bring this world in line with a diff taken from another copy of the same world
+    Inputs:     The diff to apply
+    Outputs:    Nothing if it applied, a String if this world doesn't match the one the diff was taken against, in which case nothing is changed
+    */
+    pub fn apply_diff(&mut self, diff: &WorldDiff) -> Result<(), String> {
+        let objects = locate_all(self);
+        for &(object, coordinates) in diff.removed.iter() {
+            match objects.get(&object.uid) {
+                Some(&(found, found_coordinates)) if found == object && found_coordinates == coordinates  =>  (),
+                _   =>  return Err(format!("The object with uid {} is not at ({},{}) to be removed", object.uid, coordinates.0, coordinates.1)),
+            };
+        }
+        for &(object, from, to) in diff.moved.iter() {
+            match objects.get(&object.uid) {
+                Some(&(found, found_coordinates)) if found == object && found_coordinates == from   =>  (),
+                _   =>  return Err(format!("The object with uid {} is not at ({},{}) to be moved", object.uid, from.0, from.1)),
+            };
+            if !self.data.contains_key(&to) {
+                return Err(format!("The object with uid {} cannot be moved out of bounds to ({},{})", object.uid, to.0, to.1));
+            }
+        }
+        for &(object, coordinates) in diff.added.iter() {
+            let replaced = diff.removed.iter().any(|r| r.0.uid == object.uid);
+            if objects.contains_key(&object.uid) && !replaced {
+                return Err(format!("An object with uid {} is already in the world", object.uid));
+            }
+            if !self.data.contains_key(&coordinates) {
+                return Err(format!("The object with uid {} cannot be added out of bounds at ({},{})", object.uid, coordinates.0, coordinates.1));
+            }
+        }
+
+        for &(object, _) in diff.removed.iter() {
+            let (removed, (x, y), index) = self.retrieve(object.uid).unwrap();
+            self.record(Edit::Destroy{object: removed, x: x, y: y, index: index});
+        }
+        for &(object, _, to) in diff.moved.iter() {
+            let (moved, from, from_index) = self.retrieve(object.uid).unwrap();
+            self.data.get_mut(&to).unwrap().push(moved);
+            self.record(Edit::Move{object: moved, from: from, from_index: from_index, to: to});
+        }
+        for &(object, (x, y)) in diff.added.iter() {
+            self.data.get_mut(&(x,y)).unwrap().push(object);
+            self.record(Edit::Put{object: object, x: x, y: y});
+        }
+        if diff.current_uid > self.current_uid {
+            self.current_uid = diff.current_uid;
+        }
+        Ok(())
+    }
+}
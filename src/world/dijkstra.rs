@@ -0,0 +1,194 @@
+use std::cmp::Ordering;
+use std::collections::BinaryHeap;
+use world::{
+    World,
+    WObject,
+};
+use world::pathfinding;
+use world::pathfinding::Movement;
+
+//The distance from every cell to the nearest goal, unreachable cells have no value
+#[derive(Clone, PartialEq, Debug)]
+pub struct DijkstraMap {
+    width: u32,
+    height: u32,
+    movement: Movement,
+    values: Vec<Option<i32>>,
+}
+
+#[derive(PartialEq, Eq)]
+struct OpenCell {
+    value: i32,
+    position: (u32,u32),
+}
+
+//reversed so the BinaryHeap pops the lowest value first
+impl Ord for OpenCell {
+    fn cmp(&self, other: &OpenCell) -> Ordering {
+        other.value.cmp(&self.value).then_with(|| other.position.cmp(&self.position))
+    }
+}
+
+impl PartialOrd for OpenCell {
+    fn partial_cmp(&self, other: &OpenCell) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl DijkstraMap {
+    /*
+    Signature:  new(&World,&[(u32,u32)],Movement)
+    Purpose:    Measure how far every cell is from the nearest of the goals where walls block and doors can be walked through
+    Inputs:     The world, the goal cells and which neighbours a step can reach
+    Outputs:    The map, goals that are out of bounds or inside a wall are ignored
+    */
+    pub fn new(world: &World, goals: &[(u32,u32)], movement: Movement) -> DijkstraMap {
+        DijkstraMap::with_cost(world, goals, movement, pathfinding::default_cost)
+    }
+
+    /*
+    Signature:  with_cost(&World,&[(u32,u32)],Movement,Fn(&[WObject]) -> Option<u32>)
+    Purpose:    Measure how far every cell is from the nearest of the goals with a custom cost for entering each cell
+    Inputs:     The world, the goal cells, which neighbours a step can reach and the cost of entering a cell given its objects, None for blocked
+    Outputs:    The map, goals that are out of bounds or blocked are ignored
+    */
+    pub fn with_cost<F>(world: &World, goals: &[(u32,u32)], movement: Movement, cost: F) -> DijkstraMap where F: Fn(&[WObject]) -> Option<u32> {
+        let mut map = DijkstraMap{width: world.width(), height: world.height(), movement: movement, values: vec![None; (world.width()*world.height()) as usize]};
+        for &goal in goals.iter() {
+            if pathfinding::cell_cost(world, goal, &cost).is_some() {
+                let index = map.index(goal);
+                map.values[index] = Some(0);
+            }
+        }
+        map.relax(world, &cost);
+        map
+    }
+
+    fn index(&self, position: (u32,u32)) -> usize {
+        (position.1*self.width+position.0) as usize
+    }
+
+    //Purpose: lowers every reachable cell to at most one step more than its cheapest neighbour
+    fn relax<F>(&mut self, world: &World, cost: &F) where F: Fn(&[WObject]) -> Option<u32> {
+        let mut open = BinaryHeap::new();
+        for x in 0..self.width {
+            for y in 0..self.height {
+                match self.values[self.index((x,y))] {
+                    Some(val)   =>  open.push(OpenCell{value: val, position: (x,y)}),
+                    None        =>  (),
+                };
+            }
+        }
+        while let Some(cell) = open.pop() {
+            if self.values[self.index(cell.position)] != Some(cell.value) {
+                continue;
+            }
+            for (next, step) in pathfinding::steps(world, cell.position, self.movement, cost) {
+                let new_value = cell.value.saturating_add(step as i32);
+                let index = self.index(next);
+                let improved = match self.values[index] {
+                    Some(old_value) =>  new_value < old_value,
+                    None            =>  true,
+                };
+                if improved {
+                    self.values[index] = Some(new_value);
+                    open.push(OpenCell{value: new_value, position: next});
+                }
+            }
+        }
+    }
+
+    pub fn width(&self) -> u32 {
+        self.width
+    }
+
+    pub fn height(&self) -> u32 {
+        self.height
+    }
+
+    //Purpose: the value at a cell, None if it is unreachable or out of bounds
+    pub fn value(&self, x: u32, y: u32) -> Option<i32> {
+        if x >= self.width || y >= self.height {
+            return None;
+        }
+        self.values[self.index((x,y))]
+    }
+
+    //Purpose: the reachable cell with the highest value and that value, the first found in row order on ties
+    pub fn farthest(&self) -> Option<((u32,u32), i32)> {
+        let mut best = None;
+        for y in 0..self.height {
+            for x in 0..self.width {
+                match (self.values[self.index((x,y))], best) {
+                    (Some(val), Some((_, best_val))) if val <= best_val  =>  (),
+                    (Some(val), _)  =>  best = Some(((x,y), val)),
+                    (None, _)       =>  (),
+                };
+            }
+        }
+        best
+    }
+
+    /*
+    Signature:  scaled(f64)
+    Purpose:    Multiply every value, used to weigh maps against each other before adding them
+    Inputs:     The factor to multiply by
+    Outputs:    The scaled map with each value rounded to the nearest whole number
+    */
+    pub fn scaled(&self, factor: f64) -> DijkstraMap {
+        let mut map = self.clone();
+        for value in map.values.iter_mut() {
+            *value = value.map(|v| (v as f64*factor).round() as i32);
+        }
+        map
+    }
+
+    /*
+    Signature:  inverted(&World,f64)
+    Purpose:    Build a flee map, walking downhill on it leads away from the goals while still finding the way around dead ends
+    Inputs:     The world the map was made from with new and how strongly to flee, values around 1.2 stop fleeing entities cornering themselves
+    Outputs:    The flee map
+    */
+    pub fn inverted(&self, world: &World, coefficient: f64) -> DijkstraMap {
+        self.inverted_with_cost(world, coefficient, pathfinding::default_cost)
+    }
+
+    /*
+    Signature:  inverted_with_cost(&World,f64,Fn(&[WObject]) -> Option<u32>)
+    Purpose:    Build a flee map from a map made with with_cost, so the flight keeps to the same terrain costs
+    Inputs:     The world the map was made from, how strongly to flee and the cost the map was made with
+    Outputs:    The flee map
+    */
+    pub fn inverted_with_cost<F>(&self, world: &World, coefficient: f64, cost: F) -> DijkstraMap where F: Fn(&[WObject]) -> Option<u32> {
+        let mut map = self.scaled(-coefficient);
+        map.relax(world, &cost);
+        map
+    }
+
+    //Purpose: the neighbour with the lowest value if it is lower than the cell itself, None if the cell is already at the bottom,
+    //a diagonal step is only taken when both orthogonal neighbours it passes have values
+    pub fn downhill(&self, x: u32, y: u32) -> Option<(u32,u32)> {
+        let mut best = match self.value(x, y) {
+            Some(val)   =>  (val, None),
+            None        =>  return None,
+        };
+        let deltas: &[(i64,i64)] = match self.movement {
+            Movement::Four  =>  &[(0,-1), (1,0), (0,1), (-1,0)],
+            Movement::Eight =>  &[(0,-1), (1,0), (0,1), (-1,0), (1,-1), (1,1), (-1,1), (-1,-1)],
+        };
+        for &(delta_x, delta_y) in deltas.iter() {
+            let (next_x, next_y) = (x as i64+delta_x, y as i64+delta_y);
+            if next_x < 0 || next_y < 0 {
+                continue;
+            }
+            if delta_x != 0 && delta_y != 0 && (self.value(next_x as u32, y).is_none() || self.value(x, next_y as u32).is_none()) {
+                continue;
+            }
+            match self.value(next_x as u32, next_y as u32) {
+                Some(val) if val < best.0   =>  best = (val, Some((next_x as u32, next_y as u32))),
+                _                           =>  (),
+            };
+        }
+        best.1
+    }
+}
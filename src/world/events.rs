@@ -0,0 +1,61 @@
+use world::World;
+use world::journal::Edit;
+use tile::Type;
+
+//Something that happened to an object in a world
+#[derive(Clone, PartialEq, Debug)]
+pub enum WorldEvent {
+    Placed{uid: u32, obj: Type, x: u32, y: u32},
+    Moved{uid: u32, obj: Type, from: (u32,u32), to: (u32,u32)},
+    Destroyed{uid: u32, obj: Type, x: u32, y: u32},
+}
+
+impl WorldEvent {
+    //Purpose: the event an edit causes when it is applied
+    pub fn from_edit(edit: &Edit) -> WorldEvent {
+        match *edit {
+            Edit::Put{object, x, y}             =>  WorldEvent::Placed{uid: object.uid, obj: object.obj, x: x, y: y},
+            Edit::Move{object, from, to, ..}    =>  WorldEvent::Moved{uid: object.uid, obj: object.obj, from: from, to: to},
+            Edit::Destroy{object, x, y, ..}     =>  WorldEvent::Destroyed{uid: object.uid, obj: object.obj, x: x, y: y},
+        }
+    }
+
+    //Purpose: the event that undoes this one
+    pub fn inverse(&self) -> WorldEvent {
+        match *self {
+            WorldEvent::Placed{uid, obj, x, y}      =>  WorldEvent::Destroyed{uid: uid, obj: obj, x: x, y: y},
+            WorldEvent::Moved{uid, obj, from, to}   =>  WorldEvent::Moved{uid: uid, obj: obj, from: to, to: from},
+            WorldEvent::Destroyed{uid, obj, x, y}   =>  WorldEvent::Placed{uid: uid, obj: obj, x: x, y: y},
+        }
+    }
+
+    pub fn uid(&self) -> u32 {
+        match *self {
+            WorldEvent::Placed{uid, ..}     =>  uid,
+            WorldEvent::Moved{uid, ..}      =>  uid,
+            WorldEvent::Destroyed{uid, ..}  =>  uid,
+        }
+    }
+}
+
+impl World {
+    /*
+    Signature:  subscribe(Box<FnMut(&WorldEvent) + Send>)
+    Purpose:    Register a callback that is told about every object placed, moved or destroyed, including by undo and redo
+    Inputs:     The callback
+    Outputs:    An id that can be handed to unsubscribe
+    */
+    pub fn subscribe(&mut self, observer: Box<FnMut(&WorldEvent) + Send>) -> u32 {
+        let id = self.next_observer;
+        self.next_observer = self.next_observer+1;
+        self.observers.push((id, observer));
+        id
+    }
+
+    //Purpose: removes a callback, returning false if no callback had that id
+    pub fn unsubscribe(&mut self, id: u32) -> bool {
+        let before = self.observers.len();
+        self.observers.retain(|&(observer_id, _)| observer_id != id);
+        before != self.observers.len()
+    }
+}
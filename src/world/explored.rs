@@ -0,0 +1,234 @@
+use std::collections::{
+    BTreeMap,
+    HashMap,
+    HashSet,
+};
+use std::error::Error;
+use std::fs::File;
+use std::io::{
+    Read,
+    Write,
+};
+use std::path::Path;
+use rustc_serialize::json;
+use rustc_serialize::json::{
+    ToJson,
+    Json,
+};
+use world::{
+    World,
+    WObject,
+};
+use world::format;
+use world::fov;
+
+//What one observer has seen of a world, kept after it goes out of view
+#[derive(Clone, PartialEq, Debug)]
+pub struct ExploredMap {
+    observer: u32,
+    width: u32,
+    height: u32,
+    remembered: HashMap<(u32,u32), Vec<WObject>>,
+    visible: HashSet<(u32,u32)>,
+}
+
+impl ExploredMap {
+    //Purpose: an empty memory for the observer with the given uid in a world of the given size
+    pub fn new(observer: u32, width: u32, height: u32) -> ExploredMap {
+        ExploredMap{observer: observer, width: width, height: height, remembered: HashMap::new(), visible: HashSet::new()}
+    }
+
+    pub fn observer(&self) -> u32 {
+        self.observer
+    }
+
+    /*
+    Signature:  update(&World,&HashSet<(u32,u32)>)
+    Purpose:    Remember what is in each visible cell, replacing anything remembered there before
+    Inputs:     The world and the cells the observer can see now, usually from fov::field_of_view
+    Outputs:    None, cells outside the world are ignored and the observer is never remembered in its own memory
+    */
+    pub fn update(&mut self, world: &World, visible: &HashSet<(u32,u32)>) {
+        self.visible.clear();
+        for &(x, y) in visible.iter() {
+            let objects = match world.objects_at(x, y) {
+                Some(val)   =>  val,
+                None        =>  continue,
+            };
+            let observer = self.observer;
+            self.remembered.insert((x,y), objects.into_iter().filter(|o| o.uid != observer).collect());
+            self.visible.insert((x,y));
+        }
+    }
+
+    /*
+    Signature:  look(&World,u32,bool)
+    Purpose:    Compute the observer's field of view from where it stands and remember what is in it
+    Inputs:     The world, how far the observer can see and whether doors block sight
+    Outputs:    Nothing if the observer looked around, a String if it isn't in the world
+    */
+    pub fn look(&mut self, world: &World, radius: u32, doors_opaque: bool) -> Result<(), String> {
+        let position = match world.where_is(self.observer) {
+            Some(val)   =>  val,
+            None        =>  return Err(format!("No object with the uid {} is in the world to look from", self.observer)),
+        };
+        let visible = fov::field_of_view(world, position, radius, doors_opaque);
+        self.update(world, &visible);
+        Ok(())
+    }
+
+    //Purpose: whether the cell has ever been seen
+    pub fn is_explored(&self, x: u32, y: u32) -> bool {
+        self.remembered.contains_key(&(x,y))
+    }
+
+    //Purpose: whether the cell was seen by the latest update
+    pub fn is_visible(&self, x: u32, y: u32) -> bool {
+        self.visible.contains(&(x,y))
+    }
+
+    //Purpose: what was in the cell the last time it was seen
+    pub fn remembered(&self, x: u32, y: u32) -> Option<&Vec<WObject>> {
+        self.remembered.get(&(x,y))
+    }
+
+    //Purpose: forget everything, for example when the observer changes level
+    pub fn clear(&mut self) {
+        self.remembered.clear();
+        self.visible.clear();
+    }
+
+    /*
+    Signature:  from_json(&Json)
+    Purpose:    Read an explored map back out of the Json written by to_json
+    Inputs:     The Json object
+    Outputs:    The explored map if the Json is valid, a String describing what is wrong with it if not
+    */
+    pub fn from_json(json_object: &Json) -> Result<ExploredMap, String> {
+        let map = match json_object {
+            &Json::Object(ref val)  =>  val,
+            _                       =>  return Err("The explored map is not a json object".to_string()),
+        };
+        let mut numbers = Vec::new();
+        for field in ["observer", "width", "height"].iter() {
+            match map.get(&field.to_string()) {
+                Some(&Json::U64(val)) if val <= u32::max_value() as u64 =>  numbers.push(val as u32),
+                _                       =>  return Err(format!("The explored map doesn't have a valid {} field", field)),
+            };
+        }
+        let mut explored = ExploredMap::new(numbers[0], numbers[1], numbers[2]);
+        let cells = match map.get(&"seen".to_string()) {
+            Some(&Json::Array(ref val)) =>  val,
+            _                           =>  return Err("The explored map doesn't have a valid seen field".to_string()),
+        };
+        for cell_json in cells.iter() {
+            let cell = match cell_json {
+                &Json::Object(ref val)  =>  val,
+                _                       =>  return Err("The explored map has a seen cell that is not a json object".to_string()),
+            };
+            let (x, y) = match (cell.get(&"x".to_string()), cell.get(&"y".to_string())) {
+                (Some(&Json::U64(x)), Some(&Json::U64(y))) if x < explored.width as u64 && y < explored.height as u64   =>  (x as u32, y as u32),
+                (Some(&Json::U64(x)), Some(&Json::U64(y)))  =>  return Err(format!("The explored map has a seen cell at ({},{}) outside its {}x{} size", x, y, explored.width, explored.height)),
+                _                                           =>  return Err("The explored map has a seen cell without valid coordinates".to_string()),
+            };
+            let objects_json = match cell.get(&"objects".to_string()) {
+                Some(&Json::Array(ref val)) =>  val,
+                _                           =>  return Err(format!("The explored map at ({},{}) doesn't have a valid objects field", x, y)),
+            };
+            let mut objects = Vec::new();
+            for object_json in objects_json.iter() {
+                match WObject::from_json(object_json) {
+                    Ok(val) =>  objects.push(val),
+                    Err(e)  =>  return Err(format!("The explored map at ({},{}) {}", x, y, e)),
+                };
+            }
+            explored.remembered.insert((x,y), objects);
+        }
+        Ok(explored)
+    }
+}
+
+//Only what is remembered is saved, the visible set is rebuilt by the next update
+impl ToJson for ExploredMap {
+    fn to_json(&self) -> Json {
+        let mut json_file = BTreeMap::new();
+        json_file.insert("observer".to_string(), self.observer.to_json());
+        json_file.insert("width".to_string(), self.width.to_json());
+        json_file.insert("height".to_string(), self.height.to_json());
+        let mut coordinates: Vec<&(u32,u32)> = self.remembered.keys().collect();
+        coordinates.sort();
+        let mut cells = Vec::new();
+        for &&(x, y) in coordinates.iter() {
+            let mut cell = BTreeMap::new();
+            cell.insert("x".to_string(), x.to_json());
+            cell.insert("y".to_string(), y.to_json());
+            cell.insert("objects".to_string(), self.remembered[&(x,y)].to_json());
+            cells.push(Json::Object(cell));
+        }
+        json_file.insert("seen".to_string(), Json::Array(cells));
+        Json::Object(json_file)
+    }
+}
+
+impl World {
+    /*
+    Signature:  save_with_explored(Path,&[ExploredMap])
+    Purpose:    Write the world to a file as a json save with the explored maps of its observers stored alongside it
+    Inputs:     Path object pointing to the file and the explored maps to keep
+    Outputs:    Nothing if the save was written, the error that stopped it if not, from_file still reads the world out of it
+    */
+    pub fn save_with_explored(&self, save_path: &Path, explored: &[ExploredMap]) -> Result<(), Box<Error>> {
+        let mut save_json = match self.to_json() {
+            Json::Object(val)   =>  val,
+            _                   =>  unreachable!(),
+        };
+        save_json.insert("explored".to_string(), Json::Array(explored.iter().map(|e| e.to_json()).collect()));
+        let mut save_file = match World::create_file(save_path) {
+            Ok(f)   =>  f,
+            Err(e)  =>  return Err(e),
+        };
+        match save_file.write_all(json::encode(&Json::Object(save_json)).unwrap().as_bytes()) {
+            Ok(_)   =>  Ok(()),
+            Err(e)  =>  Err(Box::new(e)),
+        }
+    }
+
+    /*
+    Signature:  from_file_with_explored(Path)
+    Purpose:    Creation of a new world object and the explored maps saved with it by save_with_explored
+    Inputs:     Path object pointing to a json save
+    Outputs:    The world and its explored maps, none for a save without them, a String explaining the error if not
+    */
+    pub fn from_file_with_explored(path_to_file: &Path) -> Result<(World, Vec<ExploredMap>), String> {
+        let mut save_string = String::new();
+        match File::open(path_to_file).and_then(|mut f| f.read_to_string(&mut save_string)) {
+            Ok(_)   =>  (),
+            Err(e)  =>  return Err(format!("File at path {} has an error of the kind {}", path_to_file.display(), e.description())),
+        };
+        let save_json = match Json::from_str(&save_string) {
+            Ok(val) =>  val,
+            Err(_)  =>  return Err("The json save is corrupted".to_string()),
+        };
+        let explored_json = match save_json.find("explored") {
+            Some(&Json::Array(ref val)) =>  val.clone(),
+            Some(_)                     =>  return Err("The json save has an explored field that is not a json array".to_string()),
+            None                        =>  Vec::new(),
+        };
+        let world = match format::load(save_json) {
+            Ok(val) =>  val,
+            Err(e)  =>  return Err(format!("The json save {}", e)),
+        };
+        let mut explored = Vec::new();
+        for map_json in explored_json.iter() {
+            let map = match ExploredMap::from_json(map_json) {
+                Ok(val) =>  val,
+                Err(e)  =>  return Err(e),
+            };
+            if map.width != world.width() || map.height != world.height() {
+                return Err(format!("The explored map of observer {} is {}x{} but the world is {}x{}", map.observer, map.width, map.height, world.width(), world.height()));
+            }
+            explored.push(map);
+        }
+        Ok((world, explored))
+    }
+}
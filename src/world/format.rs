@@ -0,0 +1,386 @@
+use std::collections::{
+    BTreeMap,
+    HashMap,
+};
+use rustc_serialize::json::{
+    ToJson,
+    Json,
+    Object,
+};
+use world::{
+    World,
+    WObject,
+};
+use tile::Type;
+
+//The layout written by to_json, saves without a format_version field are version 1, the legacy layout
+pub const FORMAT_VERSION: u64 = 2;
+
+//The largest width or height and the most cells a save may claim, checked before anything is allocated for the world
+pub const MAX_SIDE: u32 = 4096;
+pub const MAX_CELLS: u64 = 1 << 22;
+
+//MIGRATIONS[n] upgrades a save from version n+1 to n+2, a format change bumps FORMAT_VERSION and adds a step here
+const MIGRATIONS: &'static [fn(Object) -> Result<Object, String>] = &[
+    migrate_1_to_2,
+];
+
+/*
+The layout shared by the version 2 json save and the binary save, cells are always visited column by column, x then y:
+    palette     the types used as base tiles
+    tile_runs   (run length, palette index + 1) pairs covering every cell, 0 for a cell without a base tile
+    uid_runs    (first uid, run length) pairs giving the uids of the base tiles in order, each run counting up by one
+    objects     everything that isn't a base tile with its coordinates, bottom to top within a cell
+The base tile of a cell is the bottom object when it is terrain, so a generated map is mostly a few long runs.
+*/
+#[derive(Clone, PartialEq, Debug)]
+pub struct Layout {
+    pub width: u32,
+    pub height: u32,
+    pub current_uid: u32,
+    pub palette: Vec<Type>,
+    pub tile_runs: Vec<(u64, u64)>,
+    pub uid_runs: Vec<(u64, u64)>,
+    pub objects: Vec<(WObject, u32, u32)>,
+}
+
+impl Layout {
+    //Purpose: splits a world into base tile runs and the remaining objects
+    pub fn of(world: &World) -> Layout {
+        let mut layout = Layout{width: world.w, height: world.h, current_uid: world.current_uid, palette: Vec::new(), tile_runs: Vec::new(), uid_runs: Vec::new(), objects: Vec::new()};
+        for x in 0..world.w {
+            for y in 0..world.h {
+                let empty = Vec::new();
+                let z_level = match world.data.get(&(x,y)) {
+                    Some(val)   =>  val,
+                    None        =>  &empty,
+                };
+                let base = match z_level.first() {
+                    Some(object) if object.obj.is_terrain() =>  Some(*object),
+                    _                                       =>  None,
+                };
+                let index = match base {
+                    Some(object)    =>  {
+                        match layout.palette.iter().position(|t| *t == object.obj) {
+                            Some(position)  =>  position as u64+1,
+                            None            =>  {
+                                layout.palette.push(object.obj);
+                                layout.palette.len() as u64
+                            },
+                        }
+                    },
+                    None            =>  0,
+                };
+                match layout.tile_runs.last_mut() {
+                    Some(&mut (ref mut length, last_index)) if last_index == index  =>  *length = *length+1,
+                    _                                                               =>  layout.tile_runs.push((1, index)),
+                };
+                match base {
+                    Some(object)    =>  {
+                        match layout.uid_runs.last_mut() {
+                            Some(&mut (first, ref mut length)) if first+*length == object.uid as u64   =>  *length = *length+1,
+                            _                                                                           =>  layout.uid_runs.push((object.uid as u64, 1)),
+                        };
+                    },
+                    None            =>  (),
+                };
+                let skip = if base.is_some() { 1 } else { 0 };
+                for object in z_level.iter().skip(skip) {
+                    layout.objects.push((*object, x, y));
+                }
+            }
+        }
+        layout
+    }
+
+    //Purpose: checks the claimed size against the caps and the runs, so a tiny save can't make build allocate a huge world
+    fn check_size(&self) -> Result<(), String> {
+        let cell_count = self.width as u64*self.height as u64;
+        if self.width > MAX_SIDE || self.height > MAX_SIDE || cell_count > MAX_CELLS {
+            return Err(format!("is {}x{}, larger than the {}x{} and {} cell limit", self.width, self.height, MAX_SIDE, MAX_SIDE, MAX_CELLS));
+        }
+        let mut covered = 0u64;
+        let mut tiles = 0u64;
+        for &(length, index) in self.tile_runs.iter() {
+            covered = match covered.checked_add(length) {
+                Some(val) if val <= cell_count  =>  val,
+                _                               =>  return Err(format!("has tile runs covering more than the {} cells of the world", cell_count)),
+            };
+            if index != 0 {
+                tiles = tiles+length;
+            }
+        }
+        if covered != cell_count {
+            return Err(format!("has tile runs covering {} of the {} cells of the world", covered, cell_count));
+        }
+        let mut uid_count = 0u64;
+        for &(first, length) in self.uid_runs.iter() {
+            let last = first.checked_add(length);
+            uid_count = match (uid_count.checked_add(length), last) {
+                (Some(val), Some(end)) if val <= tiles && end <= u32::max_value() as u64+1  =>  val,
+                _                                                                           =>  return Err("has more tile uids than tiles".to_string()),
+            };
+        }
+        if uid_count != tiles {
+            return Err("has fewer tile uids than tiles".to_string());
+        }
+        Ok(())
+    }
+
+    /*
+    Signature:  build()
+    Purpose:    Put a world back together from its layout
+    Inputs:     None
+    Outputs:    The world if the layout is consistent, a String describing what is wrong with it if not
+    */
+    pub fn build(&self) -> Result<World, String> {
+        match self.check_size() {
+            Ok(_)   =>  (),
+            Err(e)  =>  return Err(e),
+        };
+        let mut data = HashMap::<(u32,u32), Vec<WObject>>::new();
+        let mut cells = Vec::new();
+        for x in 0..self.width {
+            for y in 0..self.height {
+                data.insert((x,y), Vec::new());
+                cells.push((x,y));
+            }
+        }
+        let mut uids = self.uid_runs.iter().flat_map(|&(first, length)| first..first.saturating_add(length));
+        let mut cell_index = 0usize;
+        for &(length, index) in self.tile_runs.iter() {
+            if cell_index as u64+length > cells.len() as u64 {
+                return Err(format!("has tile runs covering more than the {} cells of the world", cells.len()));
+            }
+            for cell in cells[cell_index..cell_index+length as usize].iter() {
+                if index == 0 {
+                    continue;
+                }
+                let obj = match self.palette.get(index as usize-1) {
+                    Some(val)   =>  *val,
+                    None        =>  return Err(format!("has a tile at ({},{}) outside the palette", cell.0, cell.1)),
+                };
+                let uid = match uids.next() {
+                    Some(val) if val <= u32::max_value() as u64 =>  val as u32,
+                    Some(val)   =>  return Err(format!("has the tile uid {}, larger than {}", val, u32::max_value())),
+                    None        =>  return Err("has fewer tile uids than tiles".to_string()),
+                };
+                data.get_mut(cell).unwrap().push(WObject{uid: uid, obj: obj});
+            }
+            cell_index = cell_index+length as usize;
+        }
+        if cell_index != cells.len() {
+            return Err(format!("has tile runs covering {} of the {} cells of the world", cell_index, cells.len()));
+        }
+        if uids.next().is_some() {
+            return Err("has more tile uids than tiles".to_string());
+        }
+        for &(object, x, y) in self.objects.iter() {
+            match data.get_mut(&(x,y)) {
+                Some(z_level)   =>  z_level.push(object),
+                None            =>  return Err(format!("has the object with uid {} out of bounds at ({},{})", object.uid, x, y)),
+            };
+        }
+        Ok(World::from_parts(self.width, self.height, self.current_uid, data))
+    }
+}
+
+impl ToJson for World {
+    fn to_json(&self) -> Json {
+        let layout = Layout::of(self);
+        let pairs = |runs: &Vec<(u64, u64)>| -> Json {
+            Json::Array(runs.iter().map(|&(a, b)| Json::Array(vec![Json::U64(a), Json::U64(b)])).collect())
+        };
+        let mut objects = Vec::new();
+        for &(object, x, y) in layout.objects.iter() {
+            let mut object_json = BTreeMap::new();
+            object_json.insert("uid".to_string(), object.uid.to_json());
+            object_json.insert("obj".to_string(), object.obj.name().to_json());
+            object_json.insert("x".to_string(), x.to_json());
+            object_json.insert("y".to_string(), y.to_json());
+            objects.push(Json::Object(object_json));
+        }
+        let mut json_file = BTreeMap::new();
+        json_file.insert("format_version".to_string(), Json::U64(FORMAT_VERSION));
+        json_file.insert("width".to_string(), Json::U64(self.w as u64));
+        json_file.insert("height".to_string(), Json::U64(self.h as u64));
+        json_file.insert("current_uid".to_string(), Json::U64(self.current_uid as u64));
+        json_file.insert("palette".to_string(), Json::Array(layout.palette.iter().map(|t| t.name().to_json()).collect()));
+        json_file.insert("tiles".to_string(), pairs(&layout.tile_runs));
+        json_file.insert("tile_uids".to_string(), pairs(&layout.uid_runs));
+        json_file.insert("objects".to_string(), Json::Array(objects));
+        Json::Object(json_file)
+    }
+}
+
+//Purpose: reads a whole number field out of a json object
+fn get_u64(map: &Object, field: &str) -> Result<u64, String> {
+    match map.get(&field.to_string()) {
+        Some(&Json::U64(val))   =>  Ok(val),
+        Some(_)                 =>  Err(format!("the {} is not the expected type", field)),
+        None                    =>  Err(format!("doesn't have a {} field", field)),
+    }
+}
+
+//Purpose: reads a number that has to fit in 32 bits out of a json object
+fn get_u32(map: &Object, field: &str) -> Result<u32, String> {
+    match get_u64(map, field) {
+        Ok(val) if val <= u32::max_value() as u64   =>  Ok(val as u32),
+        Ok(val)                                     =>  Err(format!("the {} {} is larger than {}", field, val, u32::max_value())),
+        Err(e)                                      =>  Err(e),
+    }
+}
+
+//Purpose: reads an array of [number, number] pairs out of a json object
+fn get_pairs(map: &Object, field: &str) -> Result<Vec<(u64, u64)>, String> {
+    let array = match map.get(&field.to_string()) {
+        Some(&Json::Array(ref val)) =>  val,
+        Some(_)                     =>  return Err(format!("the {} is not a json array", field)),
+        None                        =>  return Err(format!("doesn't have a {} field", field)),
+    };
+    let mut ret_vec = Vec::new();
+    for pair in array.iter() {
+        let numbers = match pair.as_array() {
+            Some(val) if val.len() == 2 =>  (val[0].as_u64(), val[1].as_u64()),
+            _                           =>  (None, None),
+        };
+        match numbers {
+            (Some(first), Some(second)) =>  ret_vec.push((first, second)),
+            _                           =>  return Err(format!("the {} has an entry that is not a pair of numbers", field)),
+        };
+    }
+    Ok(ret_vec)
+}
+
+/*
+Signature:  decode(&Object)
+Purpose:    Build a world out of a save in the current format, see Layout for what the fields hold
+Inputs:     The top level json object of the save
+Outputs:    The world if the save is valid, a String describing what is wrong with it if not
+*/
+pub fn decode(map: &Object) -> Result<World, String> {
+    let width = match get_u32(map, "width") { Ok(val) => val, Err(e) => return Err(e) };
+    let height = match get_u32(map, "height") { Ok(val) => val, Err(e) => return Err(e) };
+    let current_uid = match get_u32(map, "current_uid") { Ok(val) => val, Err(e) => return Err(e) };
+    let palette_json = match map.get(&"palette".to_string()) {
+        Some(&Json::Array(ref val)) =>  val,
+        _                           =>  return Err("doesn't have a valid palette field".to_string()),
+    };
+    let mut palette = Vec::new();
+    for entry in palette_json.iter() {
+        match entry.as_string().and_then(Type::from_name) {
+            Some(val)   =>  palette.push(val),
+            None        =>  return Err(format!("has a palette entry that is not a valid type: {}", entry)),
+        };
+    }
+    let tile_runs = match get_pairs(map, "tiles") { Ok(val) => val, Err(e) => return Err(e) };
+    let uid_runs = match get_pairs(map, "tile_uids") { Ok(val) => val, Err(e) => return Err(e) };
+    let objects_json = match map.get(&"objects".to_string()) {
+        Some(&Json::Array(ref val)) =>  val,
+        _                           =>  return Err("doesn't have a valid objects field".to_string()),
+    };
+    let mut objects = Vec::new();
+    for object_json in objects_json.iter() {
+        let object = match WObject::from_json(object_json) {
+            Ok(val) =>  val,
+            Err(e)  =>  return Err(format!("in the object list {}", e)),
+        };
+        match object_json.as_object().map(|o| (get_u32(o, "x"), get_u32(o, "y"))) {
+            Some((Ok(x), Ok(y)))    =>  objects.push((object, x, y)),
+            _                       =>  return Err(format!("has the object with uid {} without valid coordinates", object.uid)),
+        };
+    }
+    let layout = Layout{width: width, height: height, current_uid: current_uid, palette: palette, tile_runs: tile_runs, uid_runs: uid_runs, objects: objects};
+    layout.build()
+}
+
+//Purpose: the version a save was written in
+pub fn version_of(map: &Object) -> Result<u64, String> {
+    match map.get(&"format_version".to_string()) {
+        Some(&Json::U64(val))   =>  Ok(val),
+        Some(_)                 =>  Err("the format_version is not the expected type".to_string()),
+        None                    =>  Ok(1),
+    }
+}
+
+/*
+Signature:  upgrade(Json)
+Purpose:    Run a save through each migration between the version it was written in and the current one
+Inputs:     The top level json of the save
+Outputs:    The save in the current format, a String if it is corrupt or from a newer release
+*/
+pub fn upgrade(json_object: Json) -> Result<Object, String> {
+    let mut map = match json_object {
+        Json::Object(val)   =>  val,
+        _                   =>  return Err("is corrupted".to_string()),
+    };
+    let mut version = match version_of(&map) {
+        Ok(val) =>  val,
+        Err(e)  =>  return Err(e),
+    };
+    if version == 0 || version > FORMAT_VERSION {
+        return Err(format!("has the unsupported format_version {}, the newest this release reads is {}", version, FORMAT_VERSION));
+    }
+    while version < FORMAT_VERSION {
+        map = match MIGRATIONS[version as usize-1](map) {
+            Ok(val) =>  val,
+            Err(e)  =>  return Err(format!("could not be upgraded from format_version {}, {}", version, e)),
+        };
+        version = version+1;
+        map.insert("format_version".to_string(), Json::U64(version));
+    }
+    Ok(map)
+}
+
+//Purpose: upgrades a save to the current format and builds the world out of it
+pub fn load(json_object: Json) -> Result<World, String> {
+    match upgrade(json_object) {
+        Ok(map) =>  decode(&map),
+        Err(e)  =>  Err(e),
+    }
+}
+
+/*
+Signature:  migrate_1_to_2(Object)
+Purpose:    Turn a legacy save, a nested object keyed by x then y holding every cell, into a version 2 save
+Inputs:     The legacy save, the extra row and column old releases wrote past the edge are dropped
+Outputs:    The version 2 save with every object in the object list
+*/
+fn migrate_1_to_2(map: Object) -> Result<Object, String> {
+    let width = match get_u64(&map, "width") { Ok(val) => val, Err(e) => return Err(e) };
+    let height = match get_u64(&map, "height") { Ok(val) => val, Err(e) => return Err(e) };
+    let current_uid = match get_u64(&map, "current_uid") { Ok(val) => val, Err(e) => return Err(e) };
+    let mut objects = Vec::new();
+    for x in 0..width {
+        let x_level = match map.get(&x.to_string()) {
+            Some(&Json::Object(ref val))    =>  val,
+            Some(_)                         =>  return Err(format!("the x level at {} is not a json object", x)),
+            None                            =>  return Err(format!("doesn't have objects on x coordinate {}", x)),
+        };
+        for y in 0..height {
+            let z_level = match x_level.get(&y.to_string()) {
+                Some(&Json::Array(ref val)) =>  val,
+                Some(_)                     =>  return Err(format!("at the coordinates ({},{}) is not a json array", x, y)),
+                None                        =>  return Err(format!("doesn't have objects on y coordinate {} at x coordinate {}", y, x)),
+            };
+            for object_json in z_level.iter() {
+                let mut object = match object_json {
+                    &Json::Object(ref val)  =>  val.clone(),
+                    _                       =>  return Err(format!("at coordinates ({},{}) there is an item that is not a valid world object", x, y)),
+                };
+                object.insert("x".to_string(), Json::U64(x));
+                object.insert("y".to_string(), Json::U64(y));
+                objects.push(Json::Object(object));
+            }
+        }
+    }
+    let mut upgraded = BTreeMap::new();
+    upgraded.insert("width".to_string(), Json::U64(width));
+    upgraded.insert("height".to_string(), Json::U64(height));
+    upgraded.insert("current_uid".to_string(), Json::U64(current_uid));
+    upgraded.insert("palette".to_string(), Json::Array(Vec::new()));
+    upgraded.insert("tiles".to_string(), Json::Array(vec![Json::Array(vec![Json::U64(width*height), Json::U64(0)])]));
+    upgraded.insert("tile_uids".to_string(), Json::Array(Vec::new()));
+    upgraded.insert("objects".to_string(), Json::Array(objects));
+    Ok(upgraded)
+}
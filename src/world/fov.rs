@@ -0,0 +1,109 @@
+use std::collections::HashSet;
+use world::{
+    World,
+    WObject,
+};
+use tile::Type;
+
+//Purpose: whether a stack of objects stops sight, walls always do and doors only when doors_opaque is set
+pub fn blocks_sight(objects: &[WObject], doors_opaque: bool) -> bool {
+    objects.iter().any(|o| match o.obj {
+        Type::Door  =>  doors_opaque,
+        other       =>  other.is_opaque(),
+    })
+}
+
+//Purpose: whether the cell at (x,y) stops sight, cells outside the world always do
+pub fn is_opaque(world: &World, x: i64, y: i64, doors_opaque: bool) -> bool {
+    if x < 0 || y < 0 {
+        return true;
+    }
+    match world.data.get(&(x as u32, y as u32)) {
+        Some(objects)   =>  blocks_sight(objects, doors_opaque),
+        None            =>  true,
+    }
+}
+
+//multipliers that map the first octant onto each of the eight
+const OCTANTS: [[i64; 8]; 4] = [
+    [1, 0, 0, -1, -1, 0, 0, 1],
+    [0, 1, -1, 0, 0, -1, 1, 0],
+    [0, 1, 1, 0, 0, -1, -1, 0],
+    [1, 0, 0, 1, -1, 0, 0, -1],
+];
+
+struct Caster<'a> {
+    world: &'a World,
+    origin: (i64, i64),
+    radius: i64,
+    doors_opaque: bool,
+    visible: HashSet<(u32,u32)>,
+}
+
+impl<'a> Caster<'a> {
+    //Purpose: scans one octant row by row from the origin, recursing beneath each opaque run to follow the light around it
+    fn cast(&mut self, row: i64, start_slope: f64, end_slope: f64, transform: (i64, i64, i64, i64)) {
+        if start_slope < end_slope {
+            return;
+        }
+        let (xx, xy, yx, yy) = transform;
+        let mut start_slope = start_slope;
+        let mut next_start_slope = start_slope;
+        for distance in row..self.radius+1 {
+            let delta_y = -distance;
+            let mut blocked = false;
+            for delta_x in -distance..1 {
+                let left_slope = (delta_x as f64-0.5)/(delta_y as f64+0.5);
+                let right_slope = (delta_x as f64+0.5)/(delta_y as f64-0.5);
+                if start_slope < right_slope {
+                    continue;
+                }
+                else if end_slope > left_slope {
+                    break;
+                }
+                let x = self.origin.0+delta_x*xx+delta_y*xy;
+                let y = self.origin.1+delta_x*yx+delta_y*yy;
+                let in_bounds = x >= 0 && y >= 0 && x < self.world.width() as i64 && y < self.world.height() as i64;
+                if in_bounds && delta_x*delta_x+delta_y*delta_y <= self.radius*self.radius {
+                    self.visible.insert((x as u32, y as u32));
+                }
+                let opaque = is_opaque(self.world, x, y, self.doors_opaque);
+                if blocked {
+                    if opaque {
+                        next_start_slope = right_slope;
+                        continue;
+                    }
+                    blocked = false;
+                    start_slope = next_start_slope;
+                }
+                else if opaque && distance < self.radius {
+                    blocked = true;
+                    self.cast(distance+1, start_slope, left_slope, transform);
+                    next_start_slope = right_slope;
+                }
+            }
+            if blocked {
+                break;
+            }
+        }
+    }
+}
+
+/*
+Signature:  field_of_view(&World,(u32,u32),u32,bool)
+Purpose:    Find every cell that can be seen from a point using recursive shadowcasting, walls are lit but hide what is behind them
+Inputs:     The world, the point to look from, how far can be seen and whether doors block sight
+Outputs:    The set of visible cells, always including the origin when it is inside the world
+*/
+pub fn field_of_view(world: &World, origin: (u32,u32), radius: u32, doors_opaque: bool) -> HashSet<(u32,u32)> {
+    let mut caster = Caster{world: world, origin: (origin.0 as i64, origin.1 as i64), radius: radius as i64, doors_opaque: doors_opaque, visible: HashSet::new()};
+    if origin.0 >= world.width() || origin.1 >= world.height() {
+        return caster.visible;
+    }
+    caster.visible.insert(origin);
+    for octant in 0..8 {
+        let transform = (OCTANTS[0][octant], OCTANTS[1][octant], OCTANTS[2][octant], OCTANTS[3][octant]);
+        caster.cast(1, 1.0, 0.0, transform);
+    }
+    caster.visible
+}
@@ -0,0 +1,342 @@
+use world::{
+    World,
+    WObject,
+};
+use world::events::WorldEvent;
+
+//A single change made to a world, holding enough to both reverse and reapply it
+#[derive(Clone, PartialEq, Debug)]
+pub enum Edit {
+    Put{object: WObject, x: u32, y: u32},
+    Move{object: WObject, from: (u32,u32), from_index: usize, to: (u32,u32)},
+    Destroy{object: WObject, x: u32, y: u32, index: usize},
+}
+
+//Purpose: a history of edits grouped into undoable steps
+#[derive(Clone)]
+pub struct Journal {
+    done: Vec<Vec<Edit>>,
+    undone: Vec<Vec<Edit>>,
+    open_group: Option<Vec<Edit>>,
+}
+
+impl Journal {
+    pub fn new() -> Journal {
+        Journal{done: Vec::new(), undone: Vec::new(), open_group: None}
+    }
+
+    //Purpose: every edit currently applied to the world, oldest first, for replaying into a fresh world
+    pub fn edits(&self) -> Vec<Edit> {
+        let mut ret_vec = Vec::new();
+        for group in self.done.iter() {
+            for edit in group.iter() {
+                ret_vec.push(edit.clone());
+            }
+        }
+        match self.open_group {
+            Some(ref group) =>  ret_vec.extend(group.iter().cloned()),
+            None            =>  (),
+        };
+        ret_vec
+    }
+
+    pub fn in_transaction(&self) -> bool {
+        self.open_group.is_some()
+    }
+
+    pub fn can_undo(&self) -> bool {
+        self.done.len() != 0
+    }
+
+    pub fn can_redo(&self) -> bool {
+        self.undone.len() != 0
+    }
+
+    pub fn clear(&mut self) {
+        self.done.clear();
+        self.undone.clear();
+        self.open_group = None;
+    }
+
+    //Purpose: adds an edit as its own undo step, or to the open transaction, forgetting anything that was undone
+    pub fn record(&mut self, edit: Edit) {
+        self.undone.clear();
+        match self.open_group {
+            Some(ref mut group) =>  group.push(edit),
+            None                =>  self.done.push(vec![edit]),
+        };
+    }
+}
+
+//Purpose: puts an object back into a cell at the given z index, or on top if the cell is now shorter
+fn insert_object(world: &mut World, object: WObject, x: u32, y: u32, index: usize) -> Result<(), String> {
+    let z_level = match world.data.get_mut(&(x,y)) {
+        Some(val)   =>  val,
+        None        =>  return Err(format!("Could not retrieve Vector at ({},{})", x, y)),
+    };
+    if index > z_level.len() {
+        z_level.push(object);
+    }
+    else {
+        z_level.insert(index, object);
+    }
+    Ok(())
+}
+
+//Purpose: removes an object from a specific cell
+fn remove_object(world: &mut World, uid: u32, x: u32, y: u32) -> Result<WObject, String> {
+    let z_level = match world.data.get_mut(&(x,y)) {
+        Some(val)   =>  val,
+        None        =>  return Err(format!("Could not retrieve Vector at ({},{})", x, y)),
+    };
+    for index in 0..z_level.len() {
+        if z_level[index].uid == uid {
+            return Ok(z_level.remove(index));
+        }
+    }
+    Err(format!("No object with the uid {} is at ({},{})", uid, x, y))
+}
+
+//Purpose: reverses a single edit without recording it
+fn revert(world: &mut World, edit: &Edit) -> Result<(), String> {
+    match *edit {
+        Edit::Put{object, x, y} =>  {
+            match remove_object(world, object.uid, x, y) {
+                Ok(_)   =>  (),
+                Err(e)  =>  return Err(e),
+            };
+            world.current_uid = object.uid;
+            Ok(())
+        },
+        Edit::Move{object, from, from_index, to}    =>  {
+            let object = match remove_object(world, object.uid, to.0, to.1) {
+                Ok(val) =>  val,
+                Err(e)  =>  return Err(e),
+            };
+            insert_object(world, object, from.0, from.1, from_index)
+        },
+        Edit::Destroy{object, x, y, index}  =>  insert_object(world, object, x, y, index),
+    }
+}
+
+//Purpose: reapplies a single edit without recording it
+fn reapply(world: &mut World, edit: &Edit) -> Result<(), String> {
+    match *edit {
+        Edit::Put{object, x, y} =>  {
+            match insert_object(world, object, x, y, usize::max_value()) {
+                Ok(_)   =>  (),
+                Err(e)  =>  return Err(e),
+            };
+            if world.current_uid <= object.uid {
+                world.current_uid = object.uid+1;
+            }
+            Ok(())
+        },
+        Edit::Move{object, from, to, ..}    =>  {
+            let object = match remove_object(world, object.uid, from.0, from.1) {
+                Ok(val) =>  val,
+                Err(e)  =>  return Err(e),
+            };
+            insert_object(world, object, to.0, to.1, usize::max_value())
+        },
+        Edit::Destroy{object, x, y, ..} =>  remove_object(world, object.uid, x, y).map(|_| ()),
+    }
+}
+
+//Purpose: takes back the part of a step that undo or redo got through before failing, reapplying reverted edits in order or reverting reapplied ones backwards
+fn roll_back(world: &mut World, edits: &[Edit], were_reverted: bool) -> Result<(), String> {
+    if were_reverted {
+        for edit in edits.iter() {
+            match reapply(world, edit) {
+                Ok(_)   =>  world.notify(&WorldEvent::from_edit(edit)),
+                Err(e)  =>  return Err(e),
+            };
+        }
+    }
+    else {
+        for edit in edits.iter().rev() {
+            match revert(world, edit) {
+                Ok(_)   =>  world.notify(&WorldEvent::from_edit(edit).inverse()),
+                Err(e)  =>  return Err(e),
+            };
+        }
+    }
+    Ok(())
+}
+
+impl World {
+    /*
+    Signature:  enable_journal()
+    Purpose:    Start recording every put, translate and destroy so they can be undone
+    Inputs:     None
+    Outputs:    None, an already running journal is kept as it is
+    */
+    pub fn enable_journal(&mut self) {
+        if self.journal.is_none() {
+            self.journal = Some(Journal::new());
+        }
+    }
+
+    //Purpose: stops recording and throws away the history
+    pub fn disable_journal(&mut self) {
+        self.journal = None;
+    }
+
+    pub fn journal(&self) -> Option<&Journal> {
+        self.journal.as_ref()
+    }
+
+    pub fn clear_journal(&mut self) {
+        match self.journal {
+            Some(ref mut journal)   =>  journal.clear(),
+            None                    =>  (),
+        };
+    }
+
+    /*
+    Signature:  begin_transaction()
+    Purpose:    Group every edit until commit_transaction into a single undo step
+    Inputs:     None
+    Outputs:    An error if the journal is off or a transaction is already open
+    */
+    pub fn begin_transaction(&mut self) -> Result<(), String> {
+        let journal = match self.journal {
+            Some(ref mut val)   =>  val,
+            None                =>  return Err("The journal is not enabled".to_string()),
+        };
+        if journal.open_group.is_some() {
+            return Err("A transaction is already open".to_string());
+        }
+        journal.open_group = Some(Vec::new());
+        Ok(())
+    }
+
+    /*
+    Signature:  commit_transaction()
+    Purpose:    Close the open transaction, an empty transaction leaves no undo step behind
+    Inputs:     None
+    Outputs:    An error if there is no open transaction
+    */
+    pub fn commit_transaction(&mut self) -> Result<(), String> {
+        let journal = match self.journal {
+            Some(ref mut val)   =>  val,
+            None                =>  return Err("The journal is not enabled".to_string()),
+        };
+        match journal.open_group.take() {
+            Some(group) =>  {
+                if group.len() != 0 {
+                    journal.done.push(group);
+                }
+                Ok(())
+            },
+            None        =>  Err("There is no open transaction".to_string()),
+        }
+    }
+
+    /*
+    Signature:  undo()
+    Purpose:    Reverse the most recent edit or transaction, restoring the uid counter with it
+    Inputs:     None
+    Outputs:    true if something was undone, false if there was nothing to undo, a String if the world no longer matches the journal
+    */
+    pub fn undo(&mut self) -> Result<bool, String> {
+        let group = match self.journal {
+            Some(ref mut journal)   =>  {
+                if journal.open_group.is_some() {
+                    return Err("Cannot undo while a transaction is open".to_string());
+                }
+                match journal.done.pop() {
+                    Some(val)   =>  val,
+                    None        =>  return Ok(false),
+                }
+            },
+            None                    =>  return Ok(false),
+        };
+        let current_uid = self.current_uid;
+        for (count, edit) in group.iter().rev().enumerate() {
+            match revert(self, edit) {
+                Ok(_)   =>  (),
+                Err(e)  =>  {
+                    //redo what was already undone and keep the step, so the world still matches the journal
+                    let result = roll_back(self, &group[group.len()-count..], true);
+                    self.current_uid = current_uid;
+                    match self.journal {
+                        Some(ref mut journal)   =>  journal.done.push(group),
+                        None                    =>  (),
+                    };
+                    return match result {
+                        Ok(_)       =>  Err(e),
+                        Err(also)   =>  Err(format!("{}, and putting the step back failed: {}", e, also)),
+                    };
+                },
+            };
+            self.notify(&WorldEvent::from_edit(edit).inverse());
+        }
+        match self.journal {
+            Some(ref mut journal)   =>  journal.undone.push(group),
+            None                    =>  (),
+        };
+        Ok(true)
+    }
+
+    /*
+    Signature:  redo()
+    Purpose:    Reapply the most recently undone edit or transaction
+    Inputs:     None
+    Outputs:    true if something was redone, false if there was nothing to redo, a String if the world no longer matches the journal
+    */
+    pub fn redo(&mut self) -> Result<bool, String> {
+        let group = match self.journal {
+            Some(ref mut journal)   =>  {
+                if journal.open_group.is_some() {
+                    return Err("Cannot redo while a transaction is open".to_string());
+                }
+                match journal.undone.pop() {
+                    Some(val)   =>  val,
+                    None        =>  return Ok(false),
+                }
+            },
+            None                    =>  return Ok(false),
+        };
+        let current_uid = self.current_uid;
+        for (count, edit) in group.iter().enumerate() {
+            match reapply(self, edit) {
+                Ok(_)   =>  (),
+                Err(e)  =>  {
+                    //undo what was already redone and keep the step, so the world still matches the journal
+                    let result = roll_back(self, &group[..count], false);
+                    self.current_uid = current_uid;
+                    match self.journal {
+                        Some(ref mut journal)   =>  journal.undone.push(group),
+                        None                    =>  (),
+                    };
+                    return match result {
+                        Ok(_)       =>  Err(e),
+                        Err(also)   =>  Err(format!("{}, and putting the step back failed: {}", e, also)),
+                    };
+                },
+            };
+            self.notify(&WorldEvent::from_edit(edit));
+        }
+        match self.journal {
+            Some(ref mut journal)   =>  journal.done.push(group),
+            None                    =>  (),
+        };
+        Ok(true)
+    }
+
+    /*
+    Signature:  apply_edit(&Edit)
+    Purpose:    Perform a recorded edit on this world, used to replay a journal step by step into a fresh world of the same size
+    Inputs:     The edit to perform
+    Outputs:    Nothing if it applied, a String explaining why not if it didn't
+    */
+    pub fn apply_edit(&mut self, edit: &Edit) -> Result<(), String> {
+        match reapply(self, edit) {
+            Ok(_)   =>  (),
+            Err(e)  =>  return Err(e),
+        };
+        self.record(edit.clone());
+        Ok(())
+    }
+}
@@ -0,0 +1,276 @@
+use std::collections::BTreeMap;
+use rustc_serialize::json;
+use rustc_serialize::json::Json;
+use world::World;
+use tile::Type;
+
+const LDTK_VERSION: &'static str = "1.5.3";
+
+//The IntGrid value, colour and whether the type is drawn on the IntGrid layer, everything else becomes an entity
+const TERRAIN_VALUES: [(Type, u64, &'static str); 3] = [
+    (Type::HorizontalWall, 1, "#5E3C2C"),
+    (Type::VerticalWall, 2, "#5E3C2C"),
+    (Type::Floor, 3, "#A8A8A8"),
+];
+const ENTITY_COLOURS: [(Type, &'static str); 4] = [
+    (Type::Door, "#BE8A4B"),
+    (Type::MainCharacter, "#3FA34D"),
+    (Type::Monster, "#C1292E"),
+    (Type::Item, "#F1D302"),
+];
+const TERRAIN_LAYER_UID: u64 = 1;
+const ENTITY_LAYER_UID: u64 = 2;
+const FIRST_ENTITY_UID: u64 = 3;
+const FIRST_FIELD_UID: u64 = 20;
+
+//Purpose: the LDtk identifier of a type, LDtk wants capitalised identifiers without underscores
+fn identifier(obj: Type) -> String {
+    format!("{:?}", obj)
+}
+
+//Purpose: a unique iid in the uuid shape LDtk expects, counting up from the given number
+fn iid(number: u64) -> Json {
+    Json::String(format!("00000000-0000-4000-8000-{:012x}", number))
+}
+
+fn number(n: u64) -> Json {
+    Json::U64(n)
+}
+
+fn text(s: &str) -> Json {
+    Json::String(s.to_string())
+}
+
+//Purpose: the definitions shared by every level, the IntGrid and entity layers and one entity per non terrain type
+fn definitions(grid_size: u64) -> Json {
+    let mut values = Vec::new();
+    for &(obj, value, colour) in TERRAIN_VALUES.iter() {
+        let mut entry = BTreeMap::new();
+        entry.insert("value".to_string(), number(value));
+        entry.insert("identifier".to_string(), Json::String(identifier(obj)));
+        entry.insert("color".to_string(), text(colour));
+        entry.insert("tile".to_string(), Json::Null);
+        entry.insert("groupUid".to_string(), number(0));
+        values.push(Json::Object(entry));
+    }
+    let mut terrain = BTreeMap::new();
+    terrain.insert("__type".to_string(), text("IntGrid"));
+    terrain.insert("type".to_string(), text("IntGrid"));
+    terrain.insert("identifier".to_string(), text("Terrain"));
+    terrain.insert("uid".to_string(), number(TERRAIN_LAYER_UID));
+    terrain.insert("gridSize".to_string(), number(grid_size));
+    terrain.insert("displayOpacity".to_string(), number(1));
+    terrain.insert("intGridValues".to_string(), Json::Array(values));
+    let mut entities_layer = BTreeMap::new();
+    entities_layer.insert("__type".to_string(), text("Entities"));
+    entities_layer.insert("type".to_string(), text("Entities"));
+    entities_layer.insert("identifier".to_string(), text("Entities"));
+    entities_layer.insert("uid".to_string(), number(ENTITY_LAYER_UID));
+    entities_layer.insert("gridSize".to_string(), number(grid_size));
+    entities_layer.insert("displayOpacity".to_string(), number(1));
+
+    let mut entities = Vec::new();
+    for (index, &(obj, colour)) in ENTITY_COLOURS.iter().enumerate() {
+        let mut entity = BTreeMap::new();
+        entity.insert("identifier".to_string(), Json::String(identifier(obj)));
+        entity.insert("uid".to_string(), number(FIRST_ENTITY_UID+index as u64));
+        entity.insert("width".to_string(), number(grid_size));
+        entity.insert("height".to_string(), number(grid_size));
+        entity.insert("color".to_string(), text(colour));
+        entity.insert("pivotX".to_string(), number(0));
+        entity.insert("pivotY".to_string(), number(0));
+        entity.insert("tags".to_string(), Json::Array(Vec::new()));
+        let mut field = BTreeMap::new();
+        field.insert("identifier".to_string(), text("uid"));
+        field.insert("__type".to_string(), text("Int"));
+        field.insert("type".to_string(), text("F_Int"));
+        field.insert("uid".to_string(), number(FIRST_FIELD_UID+index as u64));
+        field.insert("isArray".to_string(), Json::Boolean(false));
+        field.insert("canBeNull".to_string(), Json::Boolean(false));
+        field.insert("defaultOverride".to_string(), Json::Null);
+        entity.insert("fieldDefs".to_string(), Json::Array(vec![Json::Object(field)]));
+        entities.push(Json::Object(entity));
+    }
+    let mut defs = BTreeMap::new();
+    defs.insert("layers".to_string(), Json::Array(vec![Json::Object(entities_layer), Json::Object(terrain)]));
+    defs.insert("entities".to_string(), Json::Array(entities));
+    defs.insert("tilesets".to_string(), Json::Array(Vec::new()));
+    defs.insert("enums".to_string(), Json::Array(Vec::new()));
+    defs.insert("externalEnums".to_string(), Json::Array(Vec::new()));
+    defs.insert("levelFields".to_string(), Json::Array(Vec::new()));
+    Json::Object(defs)
+}
+
+//Purpose: the fields every layer instance has, whatever its type
+fn layer_instance(name: &str, kind: &str, layer_uid: u64, level_uid: u64, world: &World, grid_size: u64, iid_number: u64) -> BTreeMap<String, Json> {
+    let mut layer = BTreeMap::new();
+    layer.insert("__identifier".to_string(), text(name));
+    layer.insert("__type".to_string(), text(kind));
+    layer.insert("__cWid".to_string(), number(world.width() as u64));
+    layer.insert("__cHei".to_string(), number(world.height() as u64));
+    layer.insert("__gridSize".to_string(), number(grid_size));
+    layer.insert("__opacity".to_string(), number(1));
+    layer.insert("__pxTotalOffsetX".to_string(), number(0));
+    layer.insert("__pxTotalOffsetY".to_string(), number(0));
+    layer.insert("__tilesetDefUid".to_string(), Json::Null);
+    layer.insert("__tilesetRelPath".to_string(), Json::Null);
+    layer.insert("iid".to_string(), iid(iid_number));
+    layer.insert("levelId".to_string(), number(level_uid));
+    layer.insert("layerDefUid".to_string(), number(layer_uid));
+    layer.insert("pxOffsetX".to_string(), number(0));
+    layer.insert("pxOffsetY".to_string(), number(0));
+    layer.insert("visible".to_string(), Json::Boolean(true));
+    layer.insert("seed".to_string(), number(0));
+    layer.insert("overrideTilesetUid".to_string(), Json::Null);
+    layer.insert("optionalRules".to_string(), Json::Array(Vec::new()));
+    layer.insert("intGridCsv".to_string(), Json::Array(Vec::new()));
+    layer.insert("autoLayerTiles".to_string(), Json::Array(Vec::new()));
+    layer.insert("gridTiles".to_string(), Json::Array(Vec::new()));
+    layer.insert("entityInstances".to_string(), Json::Array(Vec::new()));
+    layer
+}
+
+//Purpose: one level, the topmost terrain of each cell goes on the IntGrid layer and every other object becomes an entity instance
+fn level(name: &str, world: &World, level_uid: u64, world_x: u64, grid_size: u64, next_iid: &mut u64) -> Result<Json, String> {
+    let mut csv = Vec::new();
+    let mut instances = Vec::new();
+    for y in 0..world.height() {
+        for x in 0..world.width() {
+            let mut value = 0;
+            for object in world.data[&(x,y)].iter() {
+                match TERRAIN_VALUES.iter().find(|t| t.0 == object.obj) {
+                    Some(&(_, terrain_value, _))    =>  {
+                        value = terrain_value;
+                        continue;
+                    },
+                    None                            =>  (),
+                };
+                let index = match ENTITY_COLOURS.iter().position(|e| e.0 == object.obj) {
+                    Some(val)   =>  val,
+                    None        =>  return Err(format!("There is no LDtk entity for {} at ({},{})", object.obj.name(), x, y)),
+                };
+                let (px, py) = (x as u64*grid_size, y as u64*grid_size);
+                let mut entity = BTreeMap::new();
+                entity.insert("__identifier".to_string(), Json::String(identifier(object.obj)));
+                entity.insert("__grid".to_string(), Json::Array(vec![number(x as u64), number(y as u64)]));
+                entity.insert("__pivot".to_string(), Json::Array(vec![number(0), number(0)]));
+                entity.insert("__tags".to_string(), Json::Array(Vec::new()));
+                entity.insert("__tile".to_string(), Json::Null);
+                entity.insert("__smartColor".to_string(), text(ENTITY_COLOURS[index].1));
+                entity.insert("__worldX".to_string(), number(world_x+px));
+                entity.insert("__worldY".to_string(), number(py));
+                entity.insert("iid".to_string(), iid(*next_iid));
+                entity.insert("width".to_string(), number(grid_size));
+                entity.insert("height".to_string(), number(grid_size));
+                entity.insert("defUid".to_string(), number(FIRST_ENTITY_UID+index as u64));
+                entity.insert("px".to_string(), Json::Array(vec![number(px), number(py)]));
+                entity.insert("fieldInstances".to_string(), Json::Array(vec![uid_field(object.uid, FIRST_FIELD_UID+index as u64)]));
+                *next_iid = *next_iid+1;
+                instances.push(Json::Object(entity));
+            }
+            csv.push(number(value));
+        }
+    }
+    let mut entity_layer = layer_instance("Entities", "Entities", ENTITY_LAYER_UID, level_uid, world, grid_size, *next_iid);
+    entity_layer.insert("entityInstances".to_string(), Json::Array(instances));
+    let mut terrain_layer = layer_instance("Terrain", "IntGrid", TERRAIN_LAYER_UID, level_uid, world, grid_size, *next_iid+1);
+    terrain_layer.insert("intGridCsv".to_string(), Json::Array(csv));
+    *next_iid = *next_iid+2;
+
+    let mut level = BTreeMap::new();
+    level.insert("identifier".to_string(), text(name));
+    level.insert("iid".to_string(), iid(*next_iid));
+    level.insert("uid".to_string(), number(level_uid));
+    level.insert("worldX".to_string(), number(world_x));
+    level.insert("worldY".to_string(), number(0));
+    level.insert("worldDepth".to_string(), number(0));
+    level.insert("pxWid".to_string(), number(world.width() as u64*grid_size));
+    level.insert("pxHei".to_string(), number(world.height() as u64*grid_size));
+    level.insert("__bgColor".to_string(), text("#40465B"));
+    level.insert("bgColor".to_string(), Json::Null);
+    level.insert("fieldInstances".to_string(), Json::Array(Vec::new()));
+    level.insert("__neighbours".to_string(), Json::Array(Vec::new()));
+    level.insert("externalRelPath".to_string(), Json::Null);
+    //LDtk lists layer instances from the top down
+    level.insert("layerInstances".to_string(), Json::Array(vec![Json::Object(entity_layer), Json::Object(terrain_layer)]));
+    *next_iid = *next_iid+1;
+    Ok(Json::Object(level))
+}
+
+//Purpose: keeps the world uid of an entity as a custom field so engines can match it back up
+fn uid_field(uid: u32, def_uid: u64) -> Json {
+    let mut field = BTreeMap::new();
+    field.insert("__identifier".to_string(), text("uid"));
+    field.insert("__type".to_string(), text("Int"));
+    field.insert("__value".to_string(), number(uid as u64));
+    field.insert("__tile".to_string(), Json::Null);
+    field.insert("defUid".to_string(), number(def_uid));
+    field.insert("realEditorValues".to_string(), Json::Array(Vec::new()));
+    Json::Object(field)
+}
+
+/*
+Signature:  to_ldtk_project(&[(&str,&World)],u32)
+Purpose:    Write a set of worlds as the levels of an LDtk project, laid out left to right with one cell between them
+Inputs:     The level names and worlds, the names have to be valid LDtk identifiers, and the size of a cell in pixels
+Outputs:    The project json text with walls and floors on an IntGrid layer and everything else as entity instances,
+            a String naming the first object that has no LDtk entity
+*/
+pub fn to_ldtk_project(levels: &[(&str, &World)], grid_size: u32) -> Result<String, String> {
+    let grid_size = grid_size as u64;
+    let mut level_json = Vec::new();
+    let mut next_iid = 1;
+    let mut world_x = 0;
+    for (index, &(name, world)) in levels.iter().enumerate() {
+        match level(name, world, 100+index as u64, world_x, grid_size, &mut next_iid) {
+            Ok(val) =>  level_json.push(val),
+            Err(e)  =>  return Err(format!("Level {} can't be exported: {}", name, e)),
+        };
+        world_x = world_x+(world.width() as u64+1)*grid_size;
+    }
+    let mut header = BTreeMap::new();
+    header.insert("fileType".to_string(), text("LDtk Project JSON"));
+    header.insert("app".to_string(), text("LDtk"));
+    header.insert("doc".to_string(), text("https://ldtk.io/json"));
+    header.insert("schema".to_string(), text("https://ldtk.io/files/JSON_SCHEMA.json"));
+    header.insert("appVersion".to_string(), text(LDTK_VERSION));
+    header.insert("url".to_string(), text("https://ldtk.io"));
+
+    let mut root = BTreeMap::new();
+    root.insert("__header__".to_string(), Json::Object(header));
+    root.insert("iid".to_string(), iid(0));
+    root.insert("jsonVersion".to_string(), text(LDTK_VERSION));
+    root.insert("appBuildId".to_string(), number(0));
+    root.insert("nextUid".to_string(), number(100+levels.len() as u64));
+    root.insert("identifierStyle".to_string(), text("Capitalize"));
+    root.insert("worldLayout".to_string(), text("Free"));
+    root.insert("worldGridWidth".to_string(), number(grid_size));
+    root.insert("worldGridHeight".to_string(), number(grid_size));
+    root.insert("defaultGridSize".to_string(), number(grid_size));
+    root.insert("defaultEntityWidth".to_string(), number(grid_size));
+    root.insert("defaultEntityHeight".to_string(), number(grid_size));
+    root.insert("defaultPivotX".to_string(), number(0));
+    root.insert("defaultPivotY".to_string(), number(0));
+    root.insert("defaultLevelWidth".to_string(), number(levels.first().map(|l| l.1.width() as u64).unwrap_or(0)*grid_size));
+    root.insert("defaultLevelHeight".to_string(), number(levels.first().map(|l| l.1.height() as u64).unwrap_or(0)*grid_size));
+    root.insert("bgColor".to_string(), text("#40465B"));
+    root.insert("defaultLevelBgColor".to_string(), text("#40465B"));
+    root.insert("externalLevels".to_string(), Json::Boolean(false));
+    root.insert("simplifiedExport".to_string(), Json::Boolean(false));
+    root.insert("toc".to_string(), Json::Array(Vec::new()));
+    root.insert("worlds".to_string(), Json::Array(Vec::new()));
+    root.insert("flags".to_string(), Json::Array(Vec::new()));
+    root.insert("defs".to_string(), definitions(grid_size));
+    root.insert("levels".to_string(), Json::Array(level_json));
+    Ok(json::encode(&Json::Object(root)).unwrap())
+}
+
+/*
+Signature:  to_ldtk(&World,u32)
+Purpose:    Write a single world as an LDtk project with one level called Level_0
+Inputs:     The world and the size of a cell in pixels
+Outputs:    The project json text, a String naming the first object that has no LDtk entity
+*/
+pub fn to_ldtk(world: &World, grid_size: u32) -> Result<String, String> {
+    to_ldtk_project(&[("Level_0", world)], grid_size)
+}
@@ -0,0 +1,65 @@
+use world::World;
+use world::fov;
+
+//Purpose: every cell on the Bresenham line between two points, both ends included, in order from the first
+pub fn line(from: (u32,u32), to: (u32,u32)) -> Vec<(u32,u32)> {
+    let (mut x, mut y) = (from.0 as i64, from.1 as i64);
+    let (end_x, end_y) = (to.0 as i64, to.1 as i64);
+    let delta_x = (end_x-x).abs();
+    let delta_y = -(end_y-y).abs();
+    let step_x = if x < end_x { 1 } else { -1 };
+    let step_y = if y < end_y { 1 } else { -1 };
+    let mut error = delta_x+delta_y;
+    let mut ret_vec = Vec::new();
+    loop {
+        ret_vec.push((x as u32, y as u32));
+        if x == end_x && y == end_y {
+            break;
+        }
+        let doubled = 2*error;
+        if doubled >= delta_y {
+            error = error+delta_y;
+            x = x+step_x;
+        }
+        if doubled <= delta_x {
+            error = error+delta_x;
+            y = y+step_y;
+        }
+    }
+    ret_vec
+}
+
+/*
+Signature:  cells_along_line(&World,(u32,u32),(u32,u32),bool)
+Purpose:    Walk the line between two cells the way a projectile or targeting preview would
+Inputs:     The world, the start, the target and whether doors block sight
+Outputs:    The cells from the start onwards, ending with the first cell that blocks sight or the target if nothing does,
+            the line is cut short where it leaves the world
+*/
+pub fn cells_along_line(world: &World, from: (u32,u32), to: (u32,u32), doors_opaque: bool) -> Vec<(u32,u32)> {
+    let mut ret_vec = Vec::new();
+    for (index, cell) in line(from, to).into_iter().enumerate() {
+        if cell.0 >= world.width() || cell.1 >= world.height() {
+            break;
+        }
+        ret_vec.push(cell);
+        if index != 0 && fov::is_opaque(world, cell.0 as i64, cell.1 as i64, doors_opaque) {
+            break;
+        }
+    }
+    ret_vec
+}
+
+/*
+Signature:  line_of_sight(&World,(u32,u32),(u32,u32),bool)
+Purpose:    Test whether one cell can be seen from another, using the same rules as field_of_view
+Inputs:     The world, the two cells and whether doors block sight
+Outputs:    true if nothing between the two cells blocks sight, the cells themselves may be opaque
+*/
+pub fn line_of_sight(world: &World, from: (u32,u32), to: (u32,u32), doors_opaque: bool) -> bool {
+    if to.0 >= world.width() || to.1 >= world.height() {
+        return false;
+    }
+    let cells = cells_along_line(world, from, to, doors_opaque);
+    cells.last() == Some(&to)
+}
@@ -1,4 +1,26 @@
 pub mod designer;
+pub mod journal;
+pub mod diff;
+pub mod events;
+pub mod blit;
+pub mod transform;
+pub mod pathfinding;
+pub mod dijkstra;
+pub mod fov;
+pub mod los;
+pub mod explored;
+pub mod regions;
+pub mod chokepoints;
+pub mod report;
+pub mod spawn;
+pub mod format;
+pub mod binary;
+pub mod ascii;
+pub mod tiled;
+pub mod ldtk;
+pub mod rexpaint;
+pub mod render;
+pub mod svg;
 use std::vec;
 use std::string::String;
 use std::fs::{
@@ -7,20 +29,25 @@ use std::fs::{
 };
 use std::path::Path;
 use std::error::Error;
+use std::fmt;
 use std::io::prelude::*;
 use std::collections::{
 	HashMap,
 	BTreeMap,
 };
-use std::convert::AsRef;
 use rustc_serialize::json;
 use rustc_serialize::json::{
 	ToJson,
 	Json,
 };
 use tile::Type;
+use self::journal::{
+	Journal,
+	Edit,
+};
+use self::events::WorldEvent;
 
-#[derive(Clone, PartialEq, Copy)]
+#[derive(Clone, PartialEq, Copy, Debug)]
 pub struct WObject {
     pub uid: u32,
     pub obj: Type,
@@ -31,10 +58,46 @@ pub struct World{
 	h: u32,
 	current_uid: u32,
 	data: HashMap<(u32,u32), vec::Vec<WObject>>,
+	collision: bool,
+	journal: Option<Journal>,
+	observers: Vec<(u32, Box<FnMut(&WorldEvent) + Send>)>,
+	next_observer: u32,
+}
+
+#[derive(Clone, PartialEq, Debug)]
+pub enum MoveError {
+	NoSuchObject(u32),
+	OutOfBounds(i64, i64),
+	Blocked(u32, u32),
+}
+
+impl fmt::Display for MoveError {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		match *self {
+			MoveError::NoSuchObject(uid)	=>	write!(f, "No object with the uid {} is in the world", uid),
+			MoveError::OutOfBounds(x, y)	=>	write!(f, "The coordinates ({},{}) are out of bounds", x, y),
+			MoveError::Blocked(x, y)		=>	write!(f, "The cell at ({},{}) is occupied by an impassable object", x, y),
+		}
+	}
+}
+
+impl Error for MoveError {
+	fn description(&self) -> &str {
+		match *self {
+			MoveError::NoSuchObject(_)	=>	"no object with that uid",
+			MoveError::OutOfBounds(_, _)	=>	"destination out of bounds",
+			MoveError::Blocked(_, _)		=>	"destination blocked",
+		}
+	}
 }
 
 impl World{
 
+	//Purpose: builds a world around already filled in cell data with every setting at its default
+	fn from_parts(width: u32, height: u32, current_uid: u32, data: HashMap<(u32,u32), vec::Vec<WObject>>) -> World {
+		World{w:width, h:height, current_uid:current_uid, data:data, collision:false, journal:None, observers:Vec::new(), next_observer:1}
+	}
+
     /*
 	Signature:	new(u32,u32)
 	Purpose: 	Creation of a new empty world object
@@ -51,148 +114,124 @@ impl World{
 				};
 			}
 		}
-		Ok(World{w:width, h:height, current_uid: 1, data:data_map})
+		Ok(World::from_parts(width, height, 1, data_map))
 	}
 
 	/*
 	Signature:	from_file(Path)
 	Purpose:	Creation of a new world object filled with data from a file
-	Inputs:		Path object pointing to the file
+	Inputs:		Path object pointing to a json or binary save
 	Outputs:	A world object filled with the data from the file if successful, a String object explaining the error if not
 	*/
-	pub fn from_file(path_to_json_file: &Path) -> Result<World, String> {
-		let current_base_directory = Path::new("./");
-		let final_load_path = current_base_directory.join(path_to_json_file);
-		let file_name = final_load_path.display();
+	pub fn from_file(path_to_file: &Path) -> Result<World, String> {
+		let file_name = path_to_file.display();
 		//open the file
-		let mut json_file = match File::open(&final_load_path){
+		let mut save_file = match File::open(path_to_file){
 			Ok(f)	=>	f,
 			Err(e)	=>	return Err(format!("File at path {} has an error of the kind {}", file_name, e.description())),
 		};
-		//read the contents
-        let mut json_string = &mut String::new();
-		match json_file.read_to_string(json_string){
-			Ok(c)	=>	c,
-			Err(e)	=>	return Err(format!("File at path {} was not able to be read, reason: {}", file_name, e.description())),
+		match World::read_from(&mut save_file) {
+			Ok(world)	=>	Ok(world),
+			Err(e)		=>	Err(format!("File at path {}: {}", file_name, e)),
+		}
+	}
+
+	/*
+	Signature:	read_from(Read)
+	Purpose:	Creation of a new world object from any stream, such as an archive entry or an in-memory buffer
+	Inputs:		The stream, which holds either a json or a binary save
+	Outputs:	A world object filled with the data from the stream if successful, a String object explaining the error if not
+	*/
+	pub fn read_from<R: Read>(reader: &mut R) -> Result<World, String> {
+		let mut bytes = Vec::new();
+		match reader.read_to_end(&mut bytes){
+			Ok(_)	=>	(),
+			Err(e)	=>	return Err(format!("The save was not able to be read, reason: {}", e.description())),
 		};
+		if binary::is_binary(&bytes) {
+			return World::from_bytes(&bytes);
+		}
+		match String::from_utf8(bytes) {
+			Ok(json_string)	=>	World::from_json_str(&json_string),
+			Err(_)			=>	Err("The save is neither a binary save nor valid text".to_string()),
+		}
+	}
+
+	/*
+	Signature:	from_json_str(&str)
+	Purpose:	Creation of a new world object from the text of a json save
+	Inputs:		The json text, saves from older releases are upgraded
+	Outputs:	A world object filled with the data from the text if successful, a String object explaining the error if not
+	*/
+	pub fn from_json_str(json_string: &str) -> Result<World, String> {
 		//make it into a JSON enum
-		let json_object = match Json::from_str(json_string.as_ref()){
+		let json_object = match Json::from_str(json_string){
 			Ok(j)	=>	j,
-			Err(_)	=>	return Err(format!("Json file at {} is corrupted", file_name)),
+			Err(_)	=>	return Err("The json save is corrupted".to_string()),
 		};
-		//turn it into something that we can use
-		let map = match json_object {
-			Json::Object(x) 	=> 	x,
-			_			=>	return Err(format!("File at path {} is corrupted", file_name)),
-		};
-		//get the width Json enum
-		let width_json = match map.get(&"width".to_string()) {
-			Some(val)	=>	val,
-			None		=>	return Err(format!("Json file at {} doesn't have a width field", file_name))
-		};
-		//get the width
-		let width = match width_json{
-			&Json::U64(x)	=>	x,
-			_		=>	return Err(format!("Json file at path {} the width is not the expected type", file_name)),
-		};
-		//get height json enum
-		let height_json = match map.get(&"height".to_string()) {
-			Some(val)	=>	val,
-			None		=>	return Err(format!("Json file at {} doesn't have a height field", file_name))
-		};
-		//get the height
-		let height = match height_json{
-			&Json::U64(x)	=>	x,
-			_		=>	return Err(format!("Json file at path {} the height is not the expected type", file_name)),
-		};
-		//get current_uid json enum
-		let current_uid_json = match map.get(&"current_uid".to_string()) {
-			Some(val)	=>	val,
-			None		=>	return Err(format!("Json file at {} doesn't have a current_uid field", file_name))
-		};
-		//get current_uid from the enum
-		let current_uid_obj = match current_uid_json{
-			&Json::U64(x)	=>	x,
-			_		=>	return Err(format!("Json file at path {} the current_uid is not the expected type", file_name)),
-		};
-
-		let mut world_map = HashMap::<(u32,u32), Vec<WObject>>::new();
-		for x in (0..width+1) {
-			//get the x object and turn it into a BTreeMap
-			let map_x_level_json = match map.get(&x.to_string()){
-				Some(val)	=>	val,
-				None		=>	return Err(format!("Json file at path {} doesn't have objects on x coordinate {}", file_name, x)),
-			};
-			let map_x_level = match map_x_level_json {
-				&Json::Object(ref x) =>	x,
-				_				=>	return Err(format!("Json file at path {} the x level at {} is not a json object", file_name, x)),
-			};
-			//iterate through the x object
-			for y in (0..height+1) {
-				//extract the y array
-				let map_y_level_json = match map_x_level.get(&y.to_string()) {
-					Some(val)	=>	val,
-					None		=>	return Err(format!("Json file at path {} doesn't have objects on x coordinate {}", file_name, y)),
-				};
-				let z_level_json = match map_y_level_json {
-					&Json::Array(ref val)	=>	val,
-					_						=>	return Err(format!("Json file at path {} at the coordinates ({},{}) is not a json array", file_name, x, y)),
-				};
-				let mut z_level = Vec::<WObject>::new();
-				//iterate through the y array
-				for x in z_level_json.iter(){
-					//extract the object
-					let world_object = match x.clone() {
-						Json::Object(val)	=>	val,
-						_			=>	return Err(format!("Json file at path {} at coordinates ({},{}) there is an item that is not a valid world object", file_name, x, y)),
-					};
-					let uid_json = match world_object.get(&"uid".to_string()){
-						Some(val)	=>	val,
-						None		=>	return Err(format!("Json file at path {} at coordinates ({},{}), the item there has no uid", file_name, x, y)),
-					};
-					let uid_real = match uid_json {
-						&Json::U64(ref val) => val,
-						_					=>	return Err(format!("Json file at path {} at the coordinates ({},{}) is not a U64", file_name, x, y)),
-					};
-					let obj_json = match world_object.get(&"obj".to_string()) {
-						Some(val) 	=> val,
-						None		=> return Err(format!("Json file at path {} at coordinates ({},{}), the item there has no obj", file_name, x, y)),
-					};
-					let obj_real = match obj_json{
-						&Json::String(ref val)	=>	val,
-						_						=>	return Err(format!("Json file at path {} at the coordinates ({},{}) is not a String", file_name, x, y)),
-					};
-					let tile_type = match obj_real.as_ref(){
-						"H_WALL" 	=> 	Type::HorizontalWall,
-						"V_WALL"	=>	Type::VerticalWall,
-						"FLOOR"		=>	Type::Floor,
-						"MAIN_CHAR"	=>	Type::MainCharacter,
-                        "DOOR"      =>  Type::Door,
-						tile_str	=>	return Err(format!("Json file at path {} at coordinates ({},{}) is not a valid type, type in tile: {}", file_name, x, y, tile_str)),
-					};
-					z_level.push(WObject{uid:*uid_real as u32, obj:tile_type})
-				}
-				world_map.insert((x as u32, y as u32), z_level);
-			}
+		//bring saves from older releases up to the current format and build the world from that
+		match format::load(json_object) {
+			Ok(world)	=>	Ok(world),
+			Err(e)		=>	Err(format!("The json save {}", e)),
 		}
-		Ok(World{w:width as u32, h:height as u32, current_uid: current_uid_obj as u32, data:world_map})
+	}
+
+	//Purpose: the text of a json save of the world
+	pub fn to_json_string(&self) -> String {
+		json::encode(&self.to_json()).unwrap()
 	}
 
 	/*
-	
+	Signature:	save(Path)
+	Purpose:	Write the world to a file as a json save, creating the file if it doesn't exist
+	Inputs:		Path object pointing to the file
+	Outputs:	Nothing if the save was written, the error that stopped it if not
 	*/
 	pub fn save(&self, save_path: &Path) -> Result<(), Box<Error>> {
-		let current_base_directory = Path::new("./");
-		let final_save_path = current_base_directory.join(save_path);
-		let json_string = json::encode(&self.to_json()).unwrap();
-        let mut file_options = OpenOptions::new();
-        file_options.write(true);
-        file_options.truncate(true);
-		let mut json_file = match file_options.open(&final_save_path){
+		let mut save_file = match World::create_file(save_path) {
 			Ok(f)	=>	f,
-			Err(e)	=>	return Err(Box::new(e)),
+			Err(e)	=>	return Err(e),
 		};
-		match json_file.write_all(&json_string.into_bytes()){
+		self.write_to(&mut save_file)
+	}
+
+	//Purpose: the same as save but writing the binary format
+	pub fn save_binary(&self, save_path: &Path) -> Result<(), Box<Error>> {
+		let mut save_file = match World::create_file(save_path) {
+			Ok(f)	=>	f,
+			Err(e)	=>	return Err(e),
+		};
+		self.write_binary_to(&mut save_file)
+	}
+
+	//Purpose: opens a file for writing from the start, creating it if needed
+	fn create_file(save_path: &Path) -> Result<File, Box<Error>> {
+		let mut file_options = OpenOptions::new();
+		file_options.write(true);
+		file_options.create(true);
+		file_options.truncate(true);
+		match file_options.open(save_path){
+			Ok(f)	=>	Ok(f),
+			Err(e)	=>	Err(Box::new(e)),
+		}
+	}
+
+	/*
+	Signature:	write_to(Write)
+	Purpose:	Write the world to any stream as a json save
+	Inputs:		The stream
+	Outputs:	Nothing if the save was written, the error that stopped it if not
+	*/
+	pub fn write_to<W: Write>(&self, writer: &mut W) -> Result<(), Box<Error>> {
+		match writer.write_all(self.to_json_string().as_bytes()){
+			Ok(_)	=>	Ok(()),
+			Err(e)	=>	Err(Box::new(e)),
+		}
+	}
+
+	//Purpose: the same as write_to but writing the binary format
+	pub fn write_binary_to<W: Write>(&self, writer: &mut W) -> Result<(), Box<Error>> {
+		match writer.write_all(&self.to_bytes()){
 			Ok(_)	=>	Ok(()),
 			Err(e)	=>	Err(Box::new(e)),
 		}
@@ -204,8 +243,10 @@ impl World{
 			Some(val)	=>	val,
 			None		=>	return Err(format!("world data could not insert object at coordinates ({},{})", x, y)),
 		};
-		z_level.push(WObject{uid:self.current_uid, obj:obj});
+		let new_object = WObject{uid:self.current_uid, obj:obj};
+		z_level.push(new_object);
 		self.current_uid=self.current_uid+1;
+		self.record(Edit::Put{object: new_object, x: x, y: y});
 		Ok(self.current_uid-1)
 	}
 
@@ -222,6 +263,14 @@ impl World{
 		Some(ret_vec)
 	}
 
+	//Purpose: whether something can stand at (x,y), the cell has to hold something and nothing in it can be impassable
+	pub fn is_walkable(&self, x: u32, y: u32) -> bool {
+		match self.data.get(&(x,y)) {
+			Some(objects)	=>	walkable(objects),
+			None			=>	false,
+		}
+	}
+
 	pub fn where_is(&self, uid: u32) -> Option<(u32,u32)> {
 		for (coordinates, vector) in self.data.iter() {
 			for val in vector.iter(){
@@ -233,7 +282,8 @@ impl World{
 		None
 	}
 
-	fn retrieve(&mut self, uid: u32) -> Result<WObject, String> {
+	//Purpose: takes an object out of the world, returning it with the coordinates and z index it was taken from
+	fn retrieve(&mut self, uid: u32) -> Result<(WObject, (u32,u32), usize), String> {
 		let (former_x, former_y) = match self.where_is(uid){
 			Some(val)	=>	val,
 			None		=>	return Err(format!("No object with the uid {} is in the world during Retrieve where_is search", uid)),
@@ -241,29 +291,115 @@ impl World{
 		let mut former_vector = self.data.get_mut(&(former_x, former_y)).unwrap();
 		for index in (0..former_vector.len()){
 			if former_vector[index].uid == uid{
-				return Ok(former_vector.remove(index));
+				return Ok((former_vector.remove(index), (former_x, former_y), index));
 			}
 		}
 		Err(format!("No object with the uid {} is in the world", uid))
 	}
 
-	pub fn translate(&mut self, delta_x: i32, delta_y: i32, uid: u32) -> Result<(), String>{
+	/*
+	Signature:	translate(i32,i32,u32)
+	Purpose:	Move an object relative to where it currently is
+	Inputs:		The change on the x-axis, the change on the y-axis and the uid of the object
+	Outputs:	Nothing if the object was moved, a MoveError explaining why it was left in place if not
+	*/
+	pub fn translate(&mut self, delta_x: i32, delta_y: i32, uid: u32) -> Result<(), MoveError>{
 		let (former_x, former_y) = match self.where_is(uid){
 			Some(val)	=>	val,
-			None		=>	return Err(format!("No object with the uid {} is in the world during translate where_is search", uid)),
+			None		=>	return Err(MoveError::NoSuchObject(uid)),
+		};
+		let (new_x, new_y) = (former_x as i64+delta_x as i64, former_y as i64+delta_y as i64);
+		if new_x < 0 || new_y < 0 || new_x >= self.w as i64 || new_y >= self.h as i64 {
+			return Err(MoveError::OutOfBounds(new_x, new_y));
+		}
+		self.move_to(uid, new_x as u32, new_y as u32)
+	}
+
+	/*
+	Signature:	move_to(u32,u32,u32)
+	Purpose:	Move an object to an absolute position
+	Inputs:		The uid of the object followed by the x and y coordinates of the destination
+	Outputs:	Nothing if the object was moved, a MoveError explaining why it was left in place if not
+	*/
+	pub fn move_to(&mut self, uid: u32, x: u32, y: u32) -> Result<(), MoveError>{
+		if x >= self.w || y >= self.h {
+			return Err(MoveError::OutOfBounds(x as i64, y as i64));
+		}
+		if self.where_is(uid).is_none() {
+			return Err(MoveError::NoSuchObject(uid));
+		}
+		if self.collision {
+			let blocked = match self.data.get(&(x,y)) {
+				Some(val)	=>	val.iter().any(|o| o.uid != uid && !o.obj.is_passable()),
+				None		=>	return Err(MoveError::OutOfBounds(x as i64, y as i64)),
+			};
+			if blocked {
+				return Err(MoveError::Blocked(x, y));
+			}
+		}
+		let (object_being_moved, former, former_index) = match self.retrieve(uid) {
+			Ok(val)	=>	val,
+			Err(_)	=>	return Err(MoveError::NoSuchObject(uid)),
 		};
-		let object_being_moved = self.retrieve(uid).unwrap();
-		let (new_x, new_y) = (former_x as i32+delta_x, former_y as i32+delta_y);
-		let mut latter_vector = self.data.get_mut(&(new_x as u32, new_y as u32)).unwrap();
-		latter_vector.push(object_being_moved);
+		match self.data.get_mut(&(x,y)) {
+			Some(val)	=>	val.push(object_being_moved),
+			None		=>	return Err(MoveError::OutOfBounds(x as i64, y as i64)),
+		};
+		self.record(Edit::Move{object: object_being_moved, from: former, from_index: former_index, to: (x,y)});
 		Ok(())
 	}
 
+	/*
+	Signature:	set_collision(bool)
+	Purpose:	Turn on or off the refusal of moves into cells holding impassable objects such as walls
+	Inputs:		true to refuse those moves, false to allow them
+	Outputs:	None
+	*/
+	pub fn set_collision(&mut self, enabled: bool) {
+		self.collision = enabled;
+	}
+
+	pub fn collision(&self) -> bool {
+		self.collision
+	}
+
 	pub fn destroy(&mut self, uid:u32) -> Result<(), String>{
-		self.retrieve(uid).unwrap();
+		let (object, (x, y), index) = match self.retrieve(uid) {
+			Ok(val)	=>	val,
+			Err(e)	=>	return Err(e),
+		};
+		self.record(Edit::Destroy{object: object, x: x, y: y, index: index});
 		Ok(())
 	}
 
+	//Purpose: adds an edit to the journal if one is running and tells the observers about it
+	fn record(&mut self, edit: Edit) {
+		self.notify(&WorldEvent::from_edit(&edit));
+		match self.journal {
+			Some(ref mut journal)	=>	journal.record(edit),
+			None					=>	(),
+		};
+	}
+
+	/*
+	Signature:	snapshot()
+	Purpose:	Copy the world so it can be diffed against after further edits
+	Inputs:		None
+	Outputs:	A copy of the world without the journal or observers
+	*/
+	pub fn snapshot(&self) -> World {
+		let mut copy = World::from_parts(self.w, self.h, self.current_uid, self.data.clone());
+		copy.collision = self.collision;
+		copy
+	}
+
+	//Purpose: hands an event to every registered observer
+	fn notify(&mut self, event: &WorldEvent) {
+		for &mut (_, ref mut observer) in self.observers.iter_mut() {
+			observer(event);
+		}
+	}
+
 	pub fn width(&self) -> u32{
 		self.w.clone()
 	}
@@ -277,24 +413,55 @@ impl World{
 	}
 }
 
-impl ToJson for World {
-	fn to_json(&self) -> Json {
-		let mut json_file = BTreeMap::new();
-		json_file.insert("width".to_string(), Json::U64(self.w as u64));
-		json_file.insert("height".to_string(),Json::U64(self.h as u64));
-		json_file.insert("current_uid".to_string(), Json::U64(self.current_uid as u64));
-
-		for x in (0..self.w+1) {
-			let mut y_map = BTreeMap::new();
-			for y in (0..self.h+1){
-				match self.data.get(&(x,y)){
-					Some(val)	=>	y_map.insert(y.to_string(), val.to_json()),
-					None		=>	y_map.insert(y.to_string(), Vec::<WObject>::new().to_json()),
-				};
-			}
-			json_file.insert(x.to_string(), y_map.to_json());
-		}
-		json_file.to_json()
+//Purpose: whether a stack can be stood on, the void of an empty cell can't
+fn walkable(objects: &[WObject]) -> bool {
+	objects.len() != 0 && objects.iter().all(|o| o.obj.is_passable())
+}
+
+//Observers are not copied, a clone starts with none registered
+impl Clone for World {
+	fn clone(&self) -> World {
+		let mut copy = self.snapshot();
+		copy.journal = self.journal.clone();
+		copy
+	}
+}
+
+//Two worlds are equal when they have the same size, uid counter and objects in every cell, settings and history are ignored
+impl PartialEq for World {
+	fn eq(&self, other: &World) -> bool {
+		self.w == other.w && self.h == other.h && self.current_uid == other.current_uid && self.data == other.data
+	}
+}
+
+impl WObject {
+	/*
+	Signature:	from_json(&Json)
+	Purpose:	Read a world object back out of the Json written by to_json
+	Inputs:		The Json object holding the uid and obj fields
+	Outputs:	The world object if the Json is valid, a String describing what is wrong with it if not
+	*/
+	pub fn from_json(json_object: &Json) -> Result<WObject, String> {
+		let world_object = match json_object {
+			&Json::Object(ref val)	=>	val,
+			_						=>	return Err("there is an item that is not a valid world object".to_string()),
+		};
+		let uid_real = match world_object.get(&"uid".to_string()){
+			Some(&Json::U64(val)) if val <= u32::max_value() as u64	=>	val as u32,
+			Some(&Json::U64(val))	=>	return Err(format!("the uid {} of the item there is larger than {}", val, u32::max_value())),
+			Some(_)					=>	return Err("the uid of the item there is not a U64".to_string()),
+			None					=>	return Err("the item there has no uid".to_string()),
+		};
+		let obj_real = match world_object.get(&"obj".to_string()) {
+			Some(&Json::String(ref val))	=>	val,
+			Some(_)							=>	return Err("the obj of the item there is not a String".to_string()),
+			None							=>	return Err("the item there has no obj".to_string()),
+		};
+		let tile_type = match Type::from_name(obj_real.as_ref()){
+			Some(val)	=>	val,
+			None		=>	return Err(format!("is not a valid type, type in tile: {}", obj_real)),
+		};
+		Ok(WObject{uid:uid_real, obj:tile_type})
 	}
 }
 
@@ -302,13 +469,7 @@ impl ToJson for WObject {
 	fn to_json(&self) -> Json {
 		let mut json_file = BTreeMap::new();
 		json_file.insert("uid".to_string(), self.uid.to_json());
-		match self.obj{
-			Type::HorizontalWall	=>	json_file.insert("obj".to_string(), "H_WALL".to_json()),
-    		Type::VerticalWall		=>	json_file.insert("obj".to_string(), "V_WALL".to_json()),
-    		Type::Floor				=>	json_file.insert("obj".to_string(), "FLOOR".to_json()),
-    		Type::MainCharacter	    =>	json_file.insert("obj".to_string(), "MAIN_CHAR".to_json()),
-            Type::Door              =>  json_file.insert("obj".to_string(), "DOOR".to_json()),
-		};
+		json_file.insert("obj".to_string(), self.obj.name().to_json());
 		json_file.to_json()
 	}
 }
@@ -0,0 +1,169 @@
+use std::cmp::Ordering;
+use std::collections::{
+    BinaryHeap,
+    HashMap,
+};
+use world;
+use world::{
+    World,
+    WObject,
+};
+
+//Which neighbours a step can reach
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum Movement {
+    Four,
+    //diagonal steps are not allowed to squeeze past a blocked orthogonal neighbour
+    Eight,
+}
+
+#[derive(Clone, PartialEq, Debug)]
+pub struct Path {
+    //every cell from the start to the goal, both included
+    pub cells: Vec<(u32,u32)>,
+    pub cost: u32,
+}
+
+//Purpose: the cost of entering a cell, walls and empty cells block and everything else including doors costs 1
+pub fn default_cost(objects: &[WObject]) -> Option<u32> {
+    if world::walkable(objects) {
+        Some(1)
+    }
+    else {
+        None
+    }
+}
+
+//Purpose: the cells a step can reach from (x,y), ignoring whether they block
+pub fn neighbours(world: &World, x: u32, y: u32, movement: Movement) -> Vec<(u32,u32)> {
+    let deltas: &[(i64,i64)] = match movement {
+        Movement::Four  =>  &[(0,-1), (1,0), (0,1), (-1,0)],
+        Movement::Eight =>  &[(0,-1), (1,0), (0,1), (-1,0), (1,-1), (1,1), (-1,1), (-1,-1)],
+    };
+    let mut ret_vec = Vec::new();
+    for &(delta_x, delta_y) in deltas.iter() {
+        let (new_x, new_y) = (x as i64+delta_x, y as i64+delta_y);
+        if new_x >= 0 && new_y >= 0 && new_x < world.width() as i64 && new_y < world.height() as i64 {
+            ret_vec.push((new_x as u32, new_y as u32));
+        }
+    }
+    ret_vec
+}
+
+//Purpose: the cost of entering a cell, None if it blocks or is out of bounds
+pub fn cell_cost<F>(world: &World, position: (u32,u32), cost: &F) -> Option<u32> where F: Fn(&[WObject]) -> Option<u32> {
+    match world.data.get(&position) {
+        Some(objects)   =>  cost(objects).map(|c| if c < 1 { 1 } else { c }),
+        None            =>  None,
+    }
+}
+
+//Purpose: the neighbours a step from a cell can enter along with what entering them costs
+pub fn steps<F>(world: &World, from: (u32,u32), movement: Movement, cost: &F) -> Vec<((u32,u32), u32)> where F: Fn(&[WObject]) -> Option<u32> {
+    let (x, y) = from;
+    let mut ret_vec = Vec::new();
+    for next in neighbours(world, x, y, movement) {
+        let step = match cell_cost(world, next, cost) {
+            Some(val)   =>  val,
+            None        =>  continue,
+        };
+        if next.0 != x && next.1 != y && (cell_cost(world, (next.0, y), cost).is_none() || cell_cost(world, (x, next.1), cost).is_none()) {
+            continue;
+        }
+        ret_vec.push((next, step));
+    }
+    ret_vec
+}
+
+//Purpose: a lower bound on the number of steps between two cells
+fn heuristic(from: (u32,u32), to: (u32,u32), movement: Movement) -> u32 {
+    let delta_x = (from.0 as i64-to.0 as i64).abs() as u32;
+    let delta_y = (from.1 as i64-to.1 as i64).abs() as u32;
+    match movement {
+        Movement::Four  =>  delta_x+delta_y,
+        Movement::Eight =>  if delta_x > delta_y { delta_x } else { delta_y },
+    }
+}
+
+#[derive(PartialEq, Eq)]
+struct OpenNode {
+    estimate: u32,
+    cost: u32,
+    position: (u32,u32),
+}
+
+//reversed so the BinaryHeap pops the cheapest estimate first, preferring nodes closer to the goal on ties
+impl Ord for OpenNode {
+    fn cmp(&self, other: &OpenNode) -> Ordering {
+        other.estimate.cmp(&self.estimate)
+            .then_with(|| self.cost.cmp(&other.cost))
+            .then_with(|| other.position.cmp(&self.position))
+    }
+}
+
+impl PartialOrd for OpenNode {
+    fn partial_cmp(&self, other: &OpenNode) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+/*
+Signature:  find_path(&World,(u32,u32),(u32,u32),Movement)
+Purpose:    Find the shortest path between two cells where walls block and doors can be walked through
+Inputs:     The world, the start, the goal and which neighbours a step can reach
+Outputs:    The path if there is one, None if the goal can't be reached
+*/
+pub fn find_path(world: &World, start: (u32,u32), goal: (u32,u32), movement: Movement) -> Option<Path> {
+    find_path_with(world, start, goal, movement, usize::max_value(), default_cost)
+}
+
+/*
+Signature:  find_path_with(&World,(u32,u32),(u32,u32),Movement,usize,Fn(&[WObject]) -> Option<u32>)
+Purpose:    Find the cheapest path between two cells using A* with a custom cost for entering each cell
+Inputs:     The world, the start, the goal, which neighbours a step can reach, the most cells to expand before giving up and
+            the cost of entering a cell given its objects, None for blocked, costs below 1 are counted as 1
+Outputs:    The path if there is one, None if the goal can't be reached within the limit
+*/
+pub fn find_path_with<F>(world: &World, start: (u32,u32), goal: (u32,u32), movement: Movement, max_expanded: usize, cost: F) -> Option<Path> where F: Fn(&[WObject]) -> Option<u32> {
+    if !world.data.contains_key(&start) || cell_cost(world, goal, &cost).is_none() {
+        return None;
+    }
+    let mut open = BinaryHeap::new();
+    let mut best_cost = HashMap::<(u32,u32), u32>::new();
+    let mut came_from = HashMap::<(u32,u32), (u32,u32)>::new();
+    let mut expanded = 0usize;
+    best_cost.insert(start, 0);
+    open.push(OpenNode{estimate: heuristic(start, goal, movement), cost: 0, position: start});
+    while let Some(node) = open.pop() {
+        if node.position == goal {
+            let mut cells = vec![goal];
+            let mut current = goal;
+            while let Some(&previous) = came_from.get(&current) {
+                cells.push(previous);
+                current = previous;
+            }
+            cells.reverse();
+            return Some(Path{cells: cells, cost: node.cost});
+        }
+        if node.cost > best_cost[&node.position] {
+            continue;
+        }
+        expanded = expanded+1;
+        if expanded > max_expanded {
+            return None;
+        }
+        for (next, step) in steps(world, node.position, movement, &cost) {
+            let new_cost = node.cost.saturating_add(step);
+            let improved = match best_cost.get(&next) {
+                Some(&old_cost) =>  new_cost < old_cost,
+                None            =>  true,
+            };
+            if improved {
+                best_cost.insert(next, new_cost);
+                came_from.insert(next, node.position);
+                open.push(OpenNode{estimate: new_cost.saturating_add(heuristic(next, goal, movement)), cost: new_cost, position: next});
+            }
+        }
+    }
+    None
+}
@@ -0,0 +1,179 @@
+use std::collections::{
+    BTreeSet,
+    HashMap,
+};
+use world::World;
+use tile::Type;
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum RegionKind {
+    Room,
+    Corridor,
+    Door,
+}
+
+//A rectangle of cells, x and y are the upper left corner
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct Bounds {
+    pub x: u32,
+    pub y: u32,
+    pub width: u32,
+    pub height: u32,
+}
+
+impl Bounds {
+    pub fn contains(&self, x: u32, y: u32) -> bool {
+        x >= self.x && y >= self.y && x < self.x+self.width && y < self.y+self.height
+    }
+
+    pub fn center(&self) -> (u32,u32) {
+        (self.x+self.width/2, self.y+self.height/2)
+    }
+}
+
+//A connected group of cells of one kind
+#[derive(Clone, PartialEq, Debug)]
+pub struct Region {
+    pub id: usize,
+    pub kind: RegionKind,
+    pub bounds: Bounds,
+    //sorted by x then y
+    pub cells: Vec<(u32,u32)>,
+    //ids of the regions that share an edge with this one, sorted
+    pub neighbours: Vec<usize>,
+}
+
+pub struct RegionMap {
+    regions: Vec<Region>,
+    owners: HashMap<(u32,u32), usize>,
+}
+
+impl RegionMap {
+    pub fn regions(&self) -> &Vec<Region> {
+        &self.regions
+    }
+
+    pub fn region(&self, id: usize) -> Option<&Region> {
+        self.regions.get(id)
+    }
+
+    //Purpose: the region a cell belongs to, None for walls and empty cells
+    pub fn region_at(&self, x: u32, y: u32) -> Option<&Region> {
+        match self.owners.get(&(x,y)) {
+            Some(&id)   =>  self.regions.get(id),
+            None        =>  None,
+        }
+    }
+
+    pub fn rooms(&self) -> Vec<&Region> {
+        self.regions.iter().filter(|r| r.kind == RegionKind::Room).collect()
+    }
+
+    pub fn corridors(&self) -> Vec<&Region> {
+        self.regions.iter().filter(|r| r.kind == RegionKind::Corridor).collect()
+    }
+
+    pub fn doors(&self) -> Vec<&Region> {
+        self.regions.iter().filter(|r| r.kind == RegionKind::Door).collect()
+    }
+}
+
+fn is_door(world: &World, x: u32, y: u32) -> bool {
+    match world.data.get(&(x,y)) {
+        Some(objects)   =>  objects.iter().any(|o| o.obj == Type::Door),
+        None            =>  false,
+    }
+}
+
+//Purpose: a walkable cell is part of a room when it is in some 2x2 block of walkable, door free cells
+fn is_room_cell(world: &World, x: u32, y: u32) -> bool {
+    let open = |x: i64, y: i64| x >= 0 && y >= 0 && world.is_walkable(x as u32, y as u32) && !is_door(world, x as u32, y as u32);
+    let (x, y) = (x as i64, y as i64);
+    for &(delta_x, delta_y) in [(-1i64,-1i64), (0,-1), (-1,0), (0,0)].iter() {
+        let (left, top) = (x+delta_x, y+delta_y);
+        if open(left, top) && open(left+1, top) && open(left, top+1) && open(left+1, top+1) {
+            return true;
+        }
+    }
+    false
+}
+
+//Purpose: the orthogonal neighbours of a cell that are inside the world
+pub fn orthogonal(world: &World, x: u32, y: u32) -> Vec<(u32,u32)> {
+    let mut ret_vec = Vec::new();
+    if x > 0 { ret_vec.push((x-1, y)); }
+    if y > 0 { ret_vec.push((x, y-1)); }
+    if x+1 < world.width() { ret_vec.push((x+1, y)); }
+    if y+1 < world.height() { ret_vec.push((x, y+1)); }
+    ret_vec
+}
+
+/*
+Signature:  detect_regions(&World)
+Purpose:    Split the walkable part of any world into rooms, corridors and doors, a corridor being anything only one cell wide
+Inputs:     The world
+Outputs:    The regions ordered by their first cell in x then y order, with bounding boxes, cells and which regions touch
+*/
+pub fn detect_regions(world: &World) -> RegionMap {
+    let mut kinds = HashMap::new();
+    for x in 0..world.width() {
+        for y in 0..world.height() {
+            if is_door(world, x, y) {
+                kinds.insert((x,y), RegionKind::Door);
+            }
+            else if world.is_walkable(x, y) {
+                if is_room_cell(world, x, y) {
+                    kinds.insert((x,y), RegionKind::Room);
+                }
+                else {
+                    kinds.insert((x,y), RegionKind::Corridor);
+                }
+            }
+        }
+    }
+    let mut map = RegionMap{regions: Vec::new(), owners: HashMap::new()};
+    for x in 0..world.width() {
+        for y in 0..world.height() {
+            let kind = match kinds.get(&(x,y)) {
+                Some(&val)  =>  val,
+                None        =>  continue,
+            };
+            if map.owners.contains_key(&(x,y)) {
+                continue;
+            }
+            let id = map.regions.len();
+            let mut cells = Vec::new();
+            let mut stack = vec![(x,y)];
+            map.owners.insert((x,y), id);
+            while let Some(cell) = stack.pop() {
+                cells.push(cell);
+                for next in orthogonal(world, cell.0, cell.1) {
+                    if kinds.get(&next) == Some(&kind) && !map.owners.contains_key(&next) {
+                        map.owners.insert(next, id);
+                        stack.push(next);
+                    }
+                }
+            }
+            cells.sort();
+            let min_x = cells.iter().map(|c| c.0).min().unwrap();
+            let max_x = cells.iter().map(|c| c.0).max().unwrap();
+            let min_y = cells.iter().map(|c| c.1).min().unwrap();
+            let max_y = cells.iter().map(|c| c.1).max().unwrap();
+            let bounds = Bounds{x: min_x, y: min_y, width: max_x-min_x+1, height: max_y-min_y+1};
+            map.regions.push(Region{id: id, kind: kind, bounds: bounds, cells: cells, neighbours: Vec::new()});
+        }
+    }
+    for id in 0..map.regions.len() {
+        let mut neighbours = BTreeSet::new();
+        for &(x, y) in map.regions[id].cells.iter() {
+            for next in orthogonal(world, x, y) {
+                match map.owners.get(&next) {
+                    Some(&other) if other != id =>  { neighbours.insert(other); },
+                    _                           =>  (),
+                };
+            }
+        }
+        map.regions[id].neighbours = neighbours.into_iter().collect();
+    }
+    map
+}
@@ -0,0 +1,231 @@
+extern crate flate2;
+use self::flate2::Compression;
+use self::flate2::write::ZlibEncoder;
+use std::collections::HashSet;
+use std::fs::File;
+use std::io::Write;
+use std::path::Path;
+use world::World;
+use tile::Type;
+
+pub type Colour = (u8, u8, u8);
+
+//The most pixels render will draw, so a large world or cell size can't ask for more memory than a review image needs
+pub const MAX_PIXELS: u64 = 1 << 28;
+
+//Which colour each type is drawn in, a cell takes the colour of its topmost object with an entry
+#[derive(Clone, PartialEq, Debug)]
+pub struct Palette {
+    entries: Vec<(Type, Colour)>,
+    //drawn for cells with nothing in them
+    pub empty: Colour,
+}
+
+impl Palette {
+    pub fn new() -> Palette {
+        Palette{entries: Vec::new(), empty: (0, 0, 0)}
+    }
+
+    //Purpose: the colours used when nothing else is configured, grey walls, dark floor, brown doors and bright characters and items
+    pub fn standard() -> Palette {
+        let mut palette = Palette::new();
+        palette.set(Type::HorizontalWall, (170, 170, 170));
+        palette.set(Type::VerticalWall, (170, 170, 170));
+        palette.set(Type::Floor, (60, 60, 60));
+        palette.set(Type::Door, (160, 100, 40));
+        palette.set(Type::MainCharacter, (60, 200, 80));
+        palette.set(Type::Monster, (210, 50, 50));
+        palette.set(Type::Item, (240, 210, 40));
+        palette
+    }
+
+    pub fn set(&mut self, obj: Type, colour: Colour) {
+        match self.entries.iter().position(|e| e.0 == obj) {
+            Some(index) =>  self.entries[index].1 = colour,
+            None        =>  self.entries.push((obj, colour)),
+        };
+    }
+
+    pub fn colour_for(&self, obj: Type) -> Option<Colour> {
+        self.entries.iter().find(|e| e.0 == obj).map(|e| e.1)
+    }
+}
+
+//How a world is drawn
+#[derive(Clone, PartialEq, Debug)]
+pub struct RenderOptions {
+    //the width and height of the block drawn for each cell, in pixels
+    pub cell_size: u32,
+    pub palette: Palette,
+    //a one pixel line drawn around every cell in this colour
+    pub grid: Option<Colour>,
+    //cells to tint, such as a field of view or the cells of a path collected into a set, and the colour to tint them with
+    pub overlay: Option<(HashSet<(u32,u32)>, Colour)>,
+}
+
+impl RenderOptions {
+    //Purpose: blocks of a given size in the standard palette, without grid lines or an overlay
+    pub fn new(cell_size: u32) -> RenderOptions {
+        RenderOptions{cell_size: cell_size, palette: Palette::standard(), grid: None, overlay: None}
+    }
+}
+
+//An RGB image, rows top to bottom
+#[derive(Clone, PartialEq, Debug)]
+pub struct Image {
+    pub width: u32,
+    pub height: u32,
+    //three bytes per pixel
+    pub pixels: Vec<u8>,
+}
+
+impl Image {
+    pub fn pixel(&self, x: u32, y: u32) -> Colour {
+        let index = (y as usize*self.width as usize+x as usize)*3;
+        (self.pixels[index], self.pixels[index+1], self.pixels[index+2])
+    }
+
+    fn set_pixel(&mut self, x: u32, y: u32, colour: Colour) {
+        let index = (y as usize*self.width as usize+x as usize)*3;
+        self.pixels[index] = colour.0;
+        self.pixels[index+1] = colour.1;
+        self.pixels[index+2] = colour.2;
+    }
+
+    //Purpose: the image as a binary PPM, the simplest format most viewers open
+    pub fn to_ppm(&self) -> Vec<u8> {
+        let mut bytes = format!("P6\n{} {}\n255\n", self.width, self.height).into_bytes();
+        bytes.extend_from_slice(&self.pixels);
+        bytes
+    }
+
+    /*
+    Signature:  to_png(&self)
+    Purpose:    Encode the image as an 8 bit RGB PNG
+    Inputs:     None
+    Outputs:    The PNG file bytes
+    */
+    pub fn to_png(&self) -> Vec<u8> {
+        let mut header = Vec::new();
+        push_u32(&mut header, self.width);
+        push_u32(&mut header, self.height);
+        //8 bits per channel, truecolour, default compression, filtering and no interlacing
+        header.extend_from_slice(&[8, 2, 0, 0, 0]);
+        let mut scanlines = Vec::with_capacity(self.pixels.len()+self.height as usize);
+        let row_length = self.width as usize*3;
+        for y in 0..self.height as usize {
+            scanlines.push(0);
+            scanlines.extend_from_slice(&self.pixels[y*row_length..(y+1)*row_length]);
+        }
+        let mut encoder = ZlibEncoder::new(Vec::new(), Compression::default());
+        encoder.write_all(&scanlines).unwrap();
+        let data = encoder.finish().unwrap();
+
+        let mut bytes = vec![0x89, b'P', b'N', b'G', 0x0d, 0x0a, 0x1a, 0x0a];
+        push_chunk(&mut bytes, b"IHDR", &header);
+        push_chunk(&mut bytes, b"IDAT", &data);
+        push_chunk(&mut bytes, b"IEND", &[]);
+        bytes
+    }
+
+    pub fn save_png(&self, path: &Path) -> Result<(), String> {
+        write_file(path, &self.to_png())
+    }
+
+    pub fn save_ppm(&self, path: &Path) -> Result<(), String> {
+        write_file(path, &self.to_ppm())
+    }
+}
+
+fn write_file(path: &Path, bytes: &[u8]) -> Result<(), String> {
+    match File::create(path).and_then(|mut file| file.write_all(bytes)) {
+        Ok(_)   =>  Ok(()),
+        Err(e)  =>  Err(format!("Couldn't write {}: {}", path.display(), e)),
+    }
+}
+
+//Purpose: writes a big endian number, the byte order PNG uses
+fn push_u32(bytes: &mut Vec<u8>, value: u32) {
+    for shift in (0..4).rev() {
+        bytes.push((value >> (shift*8)) as u8);
+    }
+}
+
+//Purpose: the CRC-32 PNG puts after every chunk, computed bit by bit since images are small
+fn crc32(bytes: &[u8]) -> u32 {
+    let mut crc = 0xffffffffu32;
+    for &byte in bytes.iter() {
+        crc = crc ^ byte as u32;
+        for _ in 0..8 {
+            crc = if crc & 1 == 1 { (crc >> 1) ^ 0xedb88320 } else { crc >> 1 };
+        }
+    }
+    !crc
+}
+
+//Purpose: writes a PNG chunk, its length, type, data and the CRC of the type and data
+fn push_chunk(bytes: &mut Vec<u8>, kind: &[u8], data: &[u8]) {
+    push_u32(bytes, data.len() as u32);
+    let start = bytes.len();
+    bytes.extend_from_slice(kind);
+    bytes.extend_from_slice(data);
+    let crc = crc32(&bytes[start..]);
+    push_u32(bytes, crc);
+}
+
+/*
+Signature:  render(&World,&RenderOptions)
+Purpose:    Draw a world as one coloured block per cell
+Inputs:     The world and how to draw it
+Outputs:    The image, one pixel larger each way when there is a grid so the last cells are closed off,
+            a String if the cell size is 0, the world is empty or the image would be larger than MAX_PIXELS
+*/
+pub fn render(world: &World, options: &RenderOptions) -> Result<Image, String> {
+    let cell_size = options.cell_size;
+    if cell_size == 0 {
+        return Err("Cells can't be drawn 0 pixels wide".to_string());
+    }
+    if world.width() == 0 || world.height() == 0 {
+        return Err("An empty world can't be drawn".to_string());
+    }
+    let border = if options.grid.is_some() { 1 } else { 0 };
+    let (wide, high) = (world.width() as u64*cell_size as u64+border, world.height() as u64*cell_size as u64+border);
+    match wide.checked_mul(high) {
+        Some(pixels) if pixels <= MAX_PIXELS    =>  (),
+        _                                       =>  return Err(format!("A {}x{} pixel image is larger than the {} pixel limit", wide, high, MAX_PIXELS)),
+    };
+    let (width, height) = (wide as u32, high as u32);
+    let mut image = Image{width: width, height: height, pixels: vec![0; (width*height*3) as usize]};
+    for y in 0..world.height() {
+        for x in 0..world.width() {
+            let mut colour = world.data[&(x,y)].iter().rev().filter_map(|o| options.palette.colour_for(o.obj)).next().unwrap_or(options.palette.empty);
+            match options.overlay {
+                Some((ref cells, tint)) if cells.contains(&(x,y))  =>  {
+                    colour = (((colour.0 as u32+tint.0 as u32)/2) as u8, ((colour.1 as u32+tint.1 as u32)/2) as u8, ((colour.2 as u32+tint.2 as u32)/2) as u8);
+                },
+                _                                                   =>  (),
+            };
+            for py in 0..cell_size {
+                for px in 0..cell_size {
+                    image.set_pixel(x*cell_size+px, y*cell_size+py, colour);
+                }
+            }
+        }
+    }
+    match options.grid {
+        Some(colour)    =>  {
+            for x in (0..width).filter(|x| x % cell_size == 0) {
+                for y in 0..height {
+                    image.set_pixel(x, y, colour);
+                }
+            }
+            for y in (0..height).filter(|y| y % cell_size == 0) {
+                for x in 0..width {
+                    image.set_pixel(x, y, colour);
+                }
+            }
+        },
+        None            =>  (),
+    };
+    Ok(image)
+}
@@ -0,0 +1,95 @@
+use world::regions::Bounds;
+use world::designer::Direction;
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum RoomShape {
+    Rectangle,
+    Diamond,
+}
+
+#[derive(Clone, PartialEq, Debug)]
+pub struct RoomRecord {
+    pub id: usize,
+    pub shape: RoomShape,
+    //includes the walls
+    pub bounds: Bounds,
+    pub center: (u32,u32),
+}
+
+#[derive(Clone, PartialEq, Debug)]
+pub struct CorridorRecord {
+    pub id: usize,
+    //the cells at either end, the start is the left or top one
+    pub start: (u32,u32),
+    pub end: (u32,u32),
+    pub direction: Direction,
+}
+
+#[derive(Clone, PartialEq, Debug)]
+pub struct DoorRecord {
+    pub uid: u32,
+    pub x: u32,
+    pub y: u32,
+}
+
+//Everything a generator built, so spawners and minimaps don't have to work it back out of the tiles
+#[derive(Clone, PartialEq, Debug)]
+pub struct GenerationReport {
+    pub rooms: Vec<RoomRecord>,
+    pub corridors: Vec<CorridorRecord>,
+    pub doors: Vec<DoorRecord>,
+    //pairs of room ids with a way between them, the lower id first
+    pub connections: Vec<(usize,usize)>,
+}
+
+impl GenerationReport {
+    pub fn new() -> GenerationReport {
+        GenerationReport{rooms: Vec::new(), corridors: Vec::new(), doors: Vec::new(), connections: Vec::new()}
+    }
+
+    //Purpose: records a room and returns the id it was given
+    pub fn add_room(&mut self, shape: RoomShape, bounds: Bounds) -> usize {
+        let id = self.rooms.len();
+        self.rooms.push(RoomRecord{id: id, shape: shape, bounds: bounds, center: bounds.center()});
+        id
+    }
+
+    //Purpose: records a corridor and returns the id it was given
+    pub fn add_corridor(&mut self, start: (u32,u32), end: (u32,u32), direction: Direction) -> usize {
+        let id = self.corridors.len();
+        self.corridors.push(CorridorRecord{id: id, start: start, end: end, direction: direction});
+        id
+    }
+
+    pub fn add_door(&mut self, uid: u32, x: u32, y: u32) {
+        self.doors.push(DoorRecord{uid: uid, x: x, y: y});
+    }
+
+    //Purpose: records that two rooms are connected, ignoring repeats
+    pub fn connect(&mut self, first_room: usize, second_room: usize) {
+        let pair = if first_room < second_room { (first_room, second_room) } else { (second_room, first_room) };
+        if !self.connections.contains(&pair) {
+            self.connections.push(pair);
+        }
+    }
+
+    //Purpose: the ids of every room connected to the given one
+    pub fn connected_rooms(&self, room: usize) -> Vec<usize> {
+        let mut ret_vec = Vec::new();
+        for &(first, second) in self.connections.iter() {
+            if first == room {
+                ret_vec.push(second);
+            }
+            else if second == room {
+                ret_vec.push(first);
+            }
+        }
+        ret_vec.sort();
+        ret_vec
+    }
+
+    //Purpose: the room whose bounds hold the cell
+    pub fn room_at(&self, x: u32, y: u32) -> Option<&RoomRecord> {
+        self.rooms.iter().find(|r| r.bounds.contains(x, y))
+    }
+}
@@ -0,0 +1,234 @@
+extern crate flate2;
+use self::flate2::Compression;
+use self::flate2::read::GzDecoder;
+use self::flate2::write::GzEncoder;
+use std::fs::File;
+use std::io::{
+    Read,
+    Write,
+};
+use std::path::Path;
+use world::World;
+use world::format::{
+    MAX_CELLS,
+    MAX_SIDE,
+};
+use tile::Type;
+
+//The version number REXPaint writes at the start of every image
+const XP_VERSION: i32 = -1;
+//REXPaint draws cells with this background as transparent
+pub const TRANSPARENT: (u8, u8, u8) = (255, 0, 255);
+const BACKGROUND: (u8, u8, u8) = (0, 0, 0);
+//REXPaint can't make images with more layers than this
+const MAX_LAYERS: i32 = 9;
+//the most a decompressed image can hold, the header then every layer at the largest size a world can be
+const MAX_XP_BYTES: u64 = 8+MAX_LAYERS as u64*(8+MAX_CELLS*10);
+
+//Which CP437 glyph stands for which stack of objects, with the colour it is drawn in
+#[derive(Clone, PartialEq, Debug)]
+pub struct XpTable {
+    entries: Vec<(u32, Vec<Type>, (u8, u8, u8))>,
+}
+
+impl XpTable {
+    pub fn new() -> XpTable {
+        XpTable{entries: Vec::new()}
+    }
+
+    /*
+    Signature:  standard()
+    Purpose:    The glyphs used when nothing else is configured
+    Inputs:     None
+    Outputs:    A table with the CP437 double lines for walls, a middle dot for floor, + for a door,
+                and @, M and ! for the main character, a monster and an item
+    */
+    pub fn standard() -> XpTable {
+        let mut table = XpTable::new();
+        table.set(205, vec![Type::HorizontalWall], (170, 170, 170));
+        table.set(186, vec![Type::VerticalWall], (170, 170, 170));
+        table.set(250, vec![Type::Floor], (85, 85, 85));
+        table.set(43, vec![Type::Floor, Type::Door], (170, 85, 0));
+        table.set(64, vec![Type::MainCharacter], (255, 255, 85));
+        table.set(77, vec![Type::Monster], (255, 85, 85));
+        table.set(33, vec![Type::Item], (85, 255, 255));
+        table
+    }
+
+    //Purpose: makes a glyph stand for a stack, replacing what it stood for before
+    pub fn set(&mut self, glyph: u32, stack: Vec<Type>, colour: (u8, u8, u8)) {
+        match self.entries.iter().position(|e| e.0 == glyph) {
+            Some(index) =>  self.entries[index] = (glyph, stack, colour),
+            None        =>  self.entries.push((glyph, stack, colour)),
+        };
+    }
+
+    pub fn stack_for(&self, glyph: u32) -> Option<&Vec<Type>> {
+        self.entries.iter().find(|e| e.0 == glyph).map(|e| &e.1)
+    }
+
+    //Purpose: the glyph and colour of a stack, falling back to the first entry with the same top object, so a lone door is drawn as the door on floor
+    pub fn glyph_for(&self, stack: &[Type]) -> Option<(u32, (u8, u8, u8))> {
+        let exact = self.entries.iter().find(|e| e.1.as_slice() == stack);
+        let same_top = self.entries.iter().find(|e| e.1.last().is_some() && e.1.last() == stack.last());
+        exact.or(same_top).map(|e| (e.0, e.2))
+    }
+}
+
+fn push_i32(bytes: &mut Vec<u8>, value: i32) {
+    for shift in 0..4 {
+        bytes.push((value >> (shift*8)) as u8);
+    }
+}
+
+//Purpose: writes one cell, glyph, foreground then background
+fn push_cell(bytes: &mut Vec<u8>, glyph: u32, foreground: (u8, u8, u8), background: (u8, u8, u8)) {
+    push_i32(bytes, glyph as i32);
+    bytes.extend_from_slice(&[foreground.0, foreground.1, foreground.2, background.0, background.1, background.2]);
+}
+
+/*
+Signature:  to_xp(&World,&XpTable)
+Purpose:    Write a world as an uncompressed REXPaint image with the terrain on the first layer and everything else on a second
+Inputs:     The world and the glyphs to draw it with
+Outputs:    The image bytes before compression, a String naming the first stack the table has no glyph for
+*/
+pub fn to_xp(world: &World, table: &XpTable) -> Result<Vec<u8>, String> {
+    let mut bytes = Vec::new();
+    push_i32(&mut bytes, XP_VERSION);
+    push_i32(&mut bytes, 2);
+    for &terrain in [true, false].iter() {
+        push_i32(&mut bytes, world.width() as i32);
+        push_i32(&mut bytes, world.height() as i32);
+        //cells are stored a column at a time
+        for x in 0..world.width() {
+            for y in 0..world.height() {
+                let stack: Vec<Type> = world.data[&(x,y)].iter().map(|o| o.obj).filter(|o| o.is_terrain() == terrain).collect();
+                if stack.len() == 0 {
+                    push_cell(&mut bytes, 0, BACKGROUND, if terrain { BACKGROUND } else { TRANSPARENT });
+                    continue;
+                }
+                match table.glyph_for(&stack) {
+                    Some((glyph, colour))   =>  push_cell(&mut bytes, glyph, colour, BACKGROUND),
+                    None                    =>  return Err(format!("There is no glyph for {:?} at ({},{})", stack, x, y)),
+                };
+            }
+        }
+    }
+    Ok(bytes)
+}
+
+//Purpose: reads the little endian number at an offset
+fn read_i32(bytes: &[u8], offset: usize) -> Result<i32, String> {
+    if offset+4 > bytes.len() {
+        return Err("The REXPaint image ended early".to_string());
+    }
+    Ok(bytes[offset] as i32 | (bytes[offset+1] as i32) << 8 | (bytes[offset+2] as i32) << 16 | (bytes[offset+3] as i32) << 24)
+}
+
+/*
+Signature:  from_xp(&[u8],&XpTable)
+Purpose:    Creation of a new world object from an uncompressed REXPaint image
+Inputs:     The image bytes and the glyphs it was drawn with, layers are stacked in order and must all be the same size
+Outputs:    The world, a String describing what couldn't be read, transparent cells and unmapped blank glyphs are left empty
+*/
+pub fn from_xp(bytes: &[u8], table: &XpTable) -> Result<World, String> {
+    let layer_count = match (read_i32(bytes, 0), read_i32(bytes, 4)) {
+        (Ok(XP_VERSION), Ok(val)) if val > MAX_LAYERS  =>  return Err(format!("The REXPaint image has {} layers, more than the {} REXPaint allows", val, MAX_LAYERS)),
+        (Ok(XP_VERSION), Ok(val)) if val > 0   =>  val,
+        (Ok(XP_VERSION), Ok(_))                =>  return Err("The REXPaint image has no layers".to_string()),
+        (Ok(version), Ok(_))                    =>  return Err(format!("The REXPaint image has the unsupported version {}", version)),
+        _                                       =>  return Err("The REXPaint image ended early".to_string()),
+    };
+    let mut world = None;
+    let mut offset = 8;
+    for layer in 0..layer_count {
+        let (w, h) = match (read_i32(bytes, offset), read_i32(bytes, offset+4)) {
+            (Ok(w), Ok(h)) if w > 0 && h > 0    =>  (w as u32, h as u32),
+            (Ok(_), Ok(_))                      =>  return Err(format!("Layer {} of the REXPaint image has no cells", layer)),
+            _                                   =>  return Err("The REXPaint image ended early".to_string()),
+        };
+        if w > MAX_SIDE || h > MAX_SIDE || w as u64*h as u64 > MAX_CELLS {
+            return Err(format!("Layer {} of the REXPaint image is {}x{}, larger than the {}x{} and {} cell limit", layer, w, h, MAX_SIDE, MAX_SIDE, MAX_CELLS));
+        }
+        offset = offset+8;
+        let end = (w as u64).checked_mul(h as u64).and_then(|cells| cells.checked_mul(10)).and_then(|size| size.checked_add(offset as u64));
+        match end {
+            Some(val) if val <= bytes.len() as u64  =>  (),
+            _                                       =>  return Err("The REXPaint image ended early".to_string()),
+        };
+        if world.is_none() {
+            world = match World::new(w, h) {
+                Ok(val) =>  Some(val),
+                Err(e)  =>  return Err(e),
+            };
+        }
+        let world = world.as_mut().unwrap();
+        if w != world.width() || h != world.height() {
+            return Err(format!("Layer {} of the REXPaint image is {}x{} but the first is {}x{}", layer, w, h, world.width(), world.height()));
+        }
+        for x in 0..w {
+            for y in 0..h {
+                let glyph = read_i32(bytes, offset).unwrap() as u32;
+                let background = (bytes[offset+7], bytes[offset+8], bytes[offset+9]);
+                offset = offset+10;
+                if background == TRANSPARENT {
+                    continue;
+                }
+                match table.stack_for(glyph) {
+                    Some(stack) =>  for obj in stack.iter() {
+                        world.put(*obj, x, y).unwrap();
+                    },
+                    None if glyph == 0 || glyph == 32   =>  (),
+                    None                                =>  return Err(format!("Layer {} of the REXPaint image has the unknown glyph {} at ({},{})", layer, glyph, x, y)),
+                };
+            }
+        }
+    }
+    Ok(world.unwrap())
+}
+
+/*
+Signature:  save_xp(&World,&Path,&XpTable)
+Purpose:    Save a world to a gzip compressed .xp file REXPaint can open
+Inputs:     The world, the path of the file and the glyphs to draw it with
+Outputs:    Nothing, a String if a stack has no glyph or the file couldn't be written
+*/
+pub fn save_xp(world: &World, path: &Path, table: &XpTable) -> Result<(), String> {
+    let bytes = match to_xp(world, table) {
+        Ok(val) =>  val,
+        Err(e)  =>  return Err(e),
+    };
+    let file = match File::create(path) {
+        Ok(val) =>  val,
+        Err(e)  =>  return Err(format!("Couldn't create {}: {}", path.display(), e)),
+    };
+    let mut encoder = GzEncoder::new(file, Compression::default());
+    match encoder.write_all(&bytes).and_then(|_| encoder.finish()) {
+        Ok(_)   =>  Ok(()),
+        Err(e)  =>  Err(format!("Couldn't write {}: {}", path.display(), e)),
+    }
+}
+
+/*
+Signature:  load_xp(&Path,&XpTable)
+Purpose:    Creation of a new world object from a gzip compressed .xp file
+Inputs:     The path of the file and the glyphs it was drawn with
+Outputs:    The world, a String if the file couldn't be read or decompressed or holds an unknown glyph
+*/
+pub fn load_xp(path: &Path, table: &XpTable) -> Result<World, String> {
+    let file = match File::open(path) {
+        Ok(val) =>  val,
+        Err(e)  =>  return Err(format!("Couldn't open {}: {}", path.display(), e)),
+    };
+    let mut bytes = Vec::new();
+    //one byte past the limit is read so an image that is too big can be told apart from one that fits exactly
+    match GzDecoder::new(file).take(MAX_XP_BYTES+1).read_to_end(&mut bytes) {
+        Ok(_)   =>  (),
+        Err(e)  =>  return Err(format!("Couldn't decompress {}: {}", path.display(), e)),
+    };
+    if bytes.len() as u64 > MAX_XP_BYTES {
+        return Err(format!("{} decompresses to more than the {} bytes the largest image needs", path.display(), MAX_XP_BYTES));
+    }
+    from_xp(&bytes, table)
+}
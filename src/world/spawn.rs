@@ -0,0 +1,159 @@
+extern crate rand;
+use self::rand::distributions::{
+    IndependentSample,
+    Range
+};
+use self::rand::SeedableRng;
+use std::collections::HashMap;
+use world::World;
+use world::regions;
+use world::regions::RegionKind;
+use tile::Type;
+
+//One kind of thing that can be spawned and the difficulties it shows up at, both ends included
+#[derive(Clone, PartialEq, Debug)]
+pub struct SpawnEntry {
+    pub obj: Type,
+    pub weight: u32,
+    pub min_difficulty: u32,
+    pub max_difficulty: u32,
+}
+
+#[derive(Clone, PartialEq, Debug)]
+pub struct SpawnTable {
+    entries: Vec<SpawnEntry>,
+}
+
+impl SpawnTable {
+    pub fn new() -> SpawnTable {
+        SpawnTable{entries: Vec::new()}
+    }
+
+    //Purpose: adds an entry, heavier entries are picked more often
+    pub fn add(&mut self, obj: Type, weight: u32, min_difficulty: u32, max_difficulty: u32) {
+        self.entries.push(SpawnEntry{obj: obj, weight: weight, min_difficulty: min_difficulty, max_difficulty: max_difficulty});
+    }
+
+    pub fn entries(&self) -> &Vec<SpawnEntry> {
+        &self.entries
+    }
+
+    //Purpose: the entries that can spawn at a difficulty
+    fn available(&self, difficulty: u32) -> Vec<&SpawnEntry> {
+        self.entries.iter().filter(|e| e.weight > 0 && e.min_difficulty <= difficulty && difficulty <= e.max_difficulty).collect()
+    }
+}
+
+//Where things are allowed to spawn
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct SpawnRules {
+    //how many things to try to place
+    pub count: u32,
+    //the straight line distance every spawn keeps from the main character, ignored if there is no main character
+    pub min_distance_from_player: u32,
+    //the most spawns in a single room
+    pub max_per_room: u32,
+    pub allow_corridors: bool,
+}
+
+//Purpose: whether a cell is bare floor with nothing standing on it and no door
+fn is_open_floor(world: &World, x: u32, y: u32) -> bool {
+    let objects = match world.data.get(&(x,y)) {
+        Some(val)   =>  val,
+        None        =>  return false,
+    };
+    objects.iter().any(|o| o.obj == Type::Floor) && objects.iter().all(|o| o.obj.is_terrain() && o.obj != Type::Door) && world.is_walkable(x, y)
+}
+
+/*
+Signature:  spawn(&mut World,&SpawnTable,u32,&SpawnRules,&[usize])
+Purpose:    Place things picked from a spawn table onto open floor, the same seed on the same world always spawns the same things
+Inputs:     The world, the table, the difficulty to pick entries at, the placement rules and the seed
+Outputs:    The uids of everything placed, fewer than the rules asked for if the world ran out of valid cells,
+            a String if nothing in the table can spawn at the difficulty
+*/
+pub fn spawn(world: &mut World, table: &SpawnTable, difficulty: u32, rules: &SpawnRules, seed: &[usize]) -> Result<Vec<u32>, String> {
+    let entries = table.available(difficulty);
+    if entries.len() == 0 {
+        return Err(format!("Nothing in the spawn table can spawn at difficulty {}", difficulty));
+    }
+    let total_weight: u32 = entries.iter().map(|e| e.weight).sum();
+    let player = {
+        let mut found = None;
+        for x in 0..world.w {
+            for y in 0..world.h {
+                if world.data[&(x,y)].iter().any(|o| o.obj == Type::MainCharacter) && found.is_none() {
+                    found = Some((x,y));
+                }
+            }
+        }
+        found
+    };
+    let region_map = regions::detect_regions(world);
+    let mut candidates = Vec::new();
+    for x in 0..world.w {
+        for y in 0..world.h {
+            if !is_open_floor(world, x, y) {
+                continue;
+            }
+            let room = match region_map.region_at(x, y) {
+                Some(region) if region.kind == RegionKind::Room                             =>  Some(region.id),
+                Some(region) if region.kind == RegionKind::Corridor && rules.allow_corridors =>  None,
+                _                                                                           =>  continue,
+            };
+            match player {
+                Some((player_x, player_y))  =>  {
+                    let delta_x = x as i64-player_x as i64;
+                    let delta_y = y as i64-player_y as i64;
+                    if delta_x*delta_x+delta_y*delta_y < (rules.min_distance_from_player as i64)*(rules.min_distance_from_player as i64) {
+                        continue;
+                    }
+                },
+                None                        =>  (),
+            };
+            candidates.push(((x,y), room));
+        }
+    }
+
+    let mut rng = rand::StdRng::from_seed(seed);
+    let weight_range = Range::new(0, total_weight);
+    let mut per_room = HashMap::<usize, u32>::new();
+    let mut spawned = Vec::new();
+    //group the whole spawn into one undo step unless the caller already opened one
+    let own_transaction = match world.journal {
+        Some(ref journal)   =>  !journal.in_transaction(),
+        None                =>  false,
+    };
+    if own_transaction {
+        world.begin_transaction().unwrap();
+    }
+    for _ in 0..rules.count {
+        candidates.retain(|&(_, room)| match room {
+            Some(id)    =>  per_room.get(&id).map_or(true, |&placed| placed < rules.max_per_room),
+            None        =>  true,
+        });
+        if candidates.len() == 0 {
+            break;
+        }
+        let mut roll = weight_range.ind_sample(&mut rng);
+        let mut obj = entries[entries.len()-1].obj;
+        for entry in entries.iter() {
+            if roll < entry.weight {
+                obj = entry.obj;
+                break;
+            }
+            roll = roll-entry.weight;
+        }
+        let index = Range::new(0, candidates.len()).ind_sample(&mut rng);
+        let ((x, y), room) = candidates.swap_remove(index);
+        spawned.push(world.put(obj, x, y).unwrap());
+        match room {
+            Some(id)    =>  *per_room.entry(id).or_insert(0) += 1,
+            None        =>  (),
+        };
+    }
+    if own_transaction {
+        world.commit_transaction().unwrap();
+    }
+    Ok(spawned)
+}
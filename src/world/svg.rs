@@ -0,0 +1,98 @@
+use world::World;
+use world::report::GenerationReport;
+use tile::Type;
+
+const STYLE: &'static str = "\
+.floor{fill:#e8e4d8}\
+.wall{stroke:#222;stroke-linecap:square;fill:none}\
+.door{fill:#a0642a;stroke:#222}\
+.hero{fill:#2e8b3e}\
+.monster{fill:#c0392b}\
+.item{fill:#e6b800;stroke:#222}\
+.label{font-family:sans-serif;fill:#555;text-anchor:middle;dominant-baseline:middle}";
+
+//Purpose: the runs of consecutive cells holding an object of a type, along rows or along columns, as (fixed, start, end) with end included
+fn runs(world: &World, obj: Type, along_rows: bool) -> Vec<(u32, u32, u32)> {
+    let (lines, length) = if along_rows { (world.height(), world.width()) } else { (world.width(), world.height()) };
+    let mut found = Vec::new();
+    for line in 0..lines {
+        let mut start = None;
+        for position in 0..length+1 {
+            let (x, y) = if along_rows { (position, line) } else { (line, position) };
+            let has = position < length && world.data[&(x,y)].iter().any(|o| o.obj == obj);
+            match (has, start) {
+                (true, None)        =>  start = Some(position),
+                (false, Some(first))=>  {
+                    found.push((line, first, position-1));
+                    start = None;
+                },
+                _                   =>  (),
+            };
+        }
+    }
+    found
+}
+
+/*
+Signature:  to_svg(&World,u32,Option<&GenerationReport>)
+Purpose:    Draw a world as an SVG, with floors as filled rectangles, runs of walls merged into single lines,
+            doors as small squares, the main character and monsters as circles and items as diamonds
+Inputs:     The world, the size of a cell in user units and the report of the generation to label the rooms from, if any
+Outputs:    The svg text
+*/
+pub fn to_svg(world: &World, cell_size: u32, report: Option<&GenerationReport>) -> String {
+    let size = cell_size as f64;
+    let half = size/2.0;
+    //sizes in user units are worked out in u64, a large world at a large cell size doesn't fit in u32
+    let cell = cell_size as u64;
+    let (width, height) = (world.width() as u64*cell, world.height() as u64*cell);
+    let mut svg = String::new();
+    svg.push_str(&format!("<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{}\" height=\"{}\" viewBox=\"0 0 {} {}\">\n",
+        width, height, width, height));
+    svg.push_str(&format!("<style>{}</style>\n", STYLE));
+
+    svg.push_str("<g class=\"floor\">\n");
+    for (y, first, last) in runs(world, Type::Floor, true) {
+        svg.push_str(&format!("<rect x=\"{}\" y=\"{}\" width=\"{}\" height=\"{}\"/>\n", first as u64*cell, y as u64*cell, (last-first+1) as u64*cell, cell));
+    }
+    svg.push_str("</g>\n");
+
+    //walls are drawn through the middle of their cells
+    svg.push_str(&format!("<g class=\"wall\" stroke-width=\"{}\">\n", size/4.0));
+    for (y, first, last) in runs(world, Type::HorizontalWall, true) {
+        svg.push_str(&format!("<line x1=\"{}\" y1=\"{}\" x2=\"{}\" y2=\"{}\"/>\n", first as f64*size+half, y as f64*size+half, last as f64*size+half, y as f64*size+half));
+    }
+    for (x, first, last) in runs(world, Type::VerticalWall, false) {
+        svg.push_str(&format!("<line x1=\"{}\" y1=\"{}\" x2=\"{}\" y2=\"{}\"/>\n", x as f64*size+half, first as f64*size+half, x as f64*size+half, last as f64*size+half));
+    }
+    svg.push_str("</g>\n");
+
+    for y in 0..world.height() {
+        for x in 0..world.width() {
+            let (left, top) = (x as f64*size, y as f64*size);
+            for object in world.data[&(x,y)].iter() {
+                match object.obj {
+                    Type::Door          =>  svg.push_str(&format!("<rect class=\"door\" x=\"{}\" y=\"{}\" width=\"{}\" height=\"{}\"/>\n", left+size/4.0, top+size/4.0, half, half)),
+                    Type::MainCharacter =>  svg.push_str(&format!("<circle class=\"hero\" cx=\"{}\" cy=\"{}\" r=\"{}\"/>\n", left+half, top+half, size*0.35)),
+                    Type::Monster       =>  svg.push_str(&format!("<circle class=\"monster\" cx=\"{}\" cy=\"{}\" r=\"{}\"/>\n", left+half, top+half, size*0.3)),
+                    Type::Item          =>  svg.push_str(&format!("<polygon class=\"item\" points=\"{},{} {},{} {},{} {},{}\"/>\n",
+                        left+half, top+size*0.2, left+size*0.8, top+half, left+half, top+size*0.8, left+size*0.2, top+half)),
+                    _                   =>  (),
+                };
+            }
+        }
+    }
+
+    match report {
+        Some(report)    =>  {
+            svg.push_str(&format!("<g class=\"label\" font-size=\"{}\">\n", size*0.6));
+            for room in report.rooms.iter() {
+                svg.push_str(&format!("<text x=\"{}\" y=\"{}\">Room {}</text>\n", room.center.0 as f64*size+half, room.center.1 as f64*size+half, room.id));
+            }
+            svg.push_str("</g>\n");
+        },
+        None            =>  (),
+    };
+    svg.push_str("</svg>\n");
+    svg
+}
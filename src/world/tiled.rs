@@ -0,0 +1,510 @@
+use std::collections::BTreeMap;
+use rustc_serialize::json;
+use rustc_serialize::json::{
+    Json,
+    Object,
+};
+use world::World;
+use world::format::{
+    MAX_CELLS,
+    MAX_SIDE,
+};
+use tile::Type;
+
+//Which Tiled tile id stands for which type, ids start at 1 like the gids of a tileset with firstgid 1
+#[derive(Clone, PartialEq, Debug)]
+pub struct TileIdMap {
+    entries: Vec<(u32, Type)>,
+}
+
+impl TileIdMap {
+    pub fn new() -> TileIdMap {
+        TileIdMap{entries: Vec::new()}
+    }
+
+    //Purpose: the ids used when nothing else is configured, 1 to 4 for the walls, floor and door
+    pub fn standard() -> TileIdMap {
+        let mut map = TileIdMap::new();
+        map.set(1, Type::HorizontalWall).unwrap();
+        map.set(2, Type::VerticalWall).unwrap();
+        map.set(3, Type::Floor).unwrap();
+        map.set(4, Type::Door).unwrap();
+        map
+    }
+
+    //Purpose: makes an id stand for a type, replacing whatever either was mapped to before, 0 is refused since Tiled uses it for an empty cell
+    pub fn set(&mut self, id: u32, obj: Type) -> Result<(), String> {
+        if id == 0 {
+            return Err(format!("Tile id 0 marks an empty cell in Tiled and can't stand for {}", obj.name()));
+        }
+        self.entries.retain(|&(old_id, old_obj)| old_id != id && old_obj != obj);
+        self.entries.push((id, obj));
+        self.entries.sort_by(|a, b| a.0.cmp(&b.0));
+        Ok(())
+    }
+
+    pub fn id_for(&self, obj: Type) -> Option<u32> {
+        self.entries.iter().find(|e| e.1 == obj).map(|e| e.0)
+    }
+
+    pub fn type_for(&self, id: u32) -> Option<Type> {
+        self.entries.iter().find(|e| e.0 == id).map(|e| e.1)
+    }
+}
+
+//A world split the way Tiled sees it, terrain goes into tile layers and everything else into the object layer
+struct TiledMap {
+    width: u32,
+    height: u32,
+    tile_width: u32,
+    tile_height: u32,
+    //one entry per layer, each holding a tile id per cell in row order, 0 for no tile
+    layers: Vec<Vec<u32>>,
+    //uid, type name and the pixel position of the upper left corner
+    objects: Vec<(u32, String, u32, u32)>,
+    next_object_id: u32,
+}
+
+//Purpose: splits a world into tile layers and objects, the nth layer holds the nth terrain object of each cell
+fn split(world: &World, ids: &TileIdMap, tile_width: u32, tile_height: u32) -> Result<TiledMap, String> {
+    if tile_width == 0 || tile_height == 0 {
+        return Err(format!("A tile can't be {}x{} pixels", tile_width, tile_height));
+    }
+    let cell_count = (world.width() as u64*world.height() as u64) as usize;
+    let mut map = TiledMap{width: world.width(), height: world.height(), tile_width: tile_width, tile_height: tile_height, layers: vec![vec![0; cell_count]], objects: Vec::new(), next_object_id: world.current_uid};
+    for y in 0..world.height() {
+        for x in 0..world.width() {
+            let mut layer = 0;
+            for object in world.data[&(x,y)].iter() {
+                if !object.obj.is_terrain() {
+                    //pixel positions are worked out wide so a large tile can't wrap them around
+                    let (pixel_x, pixel_y) = (x as u64*tile_width as u64, y as u64*tile_height as u64);
+                    if pixel_x > u32::max_value() as u64 || pixel_y > u32::max_value() as u64 {
+                        return Err(format!("The {} at ({},{}) is at pixel ({},{}), too far for a Tiled map", object.obj.name(), x, y, pixel_x, pixel_y));
+                    }
+                    map.objects.push((object.uid, object.obj.name().to_string(), pixel_x as u32, pixel_y as u32));
+                    continue;
+                }
+                let id = match ids.id_for(object.obj) {
+                    Some(val)   =>  val,
+                    None        =>  return Err(format!("There is no tile id for {} at ({},{})", object.obj.name(), x, y)),
+                };
+                if layer == map.layers.len() {
+                    map.layers.push(vec![0; cell_count]);
+                }
+                map.layers[layer][(y*world.width()+x) as usize] = id;
+                layer = layer+1;
+            }
+        }
+    }
+    map.objects.sort();
+    Ok(map)
+}
+
+//Purpose: puts a world back together from its layers, bottom layer first and the objects last in id order
+fn join(map: &TiledMap, ids: &TileIdMap, first_gid: u32) -> Result<World, String> {
+    if map.width > MAX_SIDE || map.height > MAX_SIDE || map.width as u64*map.height as u64 > MAX_CELLS {
+        return Err(format!("The Tiled map is {}x{}, larger than the {}x{} and {} cell limit", map.width, map.height, MAX_SIDE, MAX_SIDE, MAX_CELLS));
+    }
+    let mut world = match World::new(map.width, map.height) {
+        Ok(val) =>  val,
+        Err(e)  =>  return Err(e),
+    };
+    for (layer_index, layer) in map.layers.iter().enumerate() {
+        if layer.len() != (map.width*map.height) as usize {
+            return Err(format!("Tile layer {} has {} tiles for a {}x{} map", layer_index, layer.len(), map.width, map.height));
+        }
+        for (index, &gid) in layer.iter().enumerate() {
+            //the top bits of a gid are flip flags
+            let gid = gid & 0x1fffffff;
+            if gid == 0 {
+                continue;
+            }
+            let (x, y) = (index as u32 % map.width, index as u32/map.width);
+            let obj = match gid.checked_sub(first_gid).and_then(|local| ids.type_for(local+1)) {
+                Some(val)   =>  val,
+                None        =>  return Err(format!("Tile layer {} has the unknown tile id {} at ({},{})", layer_index, gid, x, y)),
+            };
+            world.put(obj, x, y).unwrap();
+        }
+    }
+    let mut objects = map.objects.clone();
+    objects.sort();
+    for &(id, ref name, pixel_x, pixel_y) in objects.iter() {
+        let obj = match Type::from_name(name) {
+            Some(val)   =>  val,
+            None        =>  return Err(format!("Object {} has the unknown type {}", id, name)),
+        };
+        let (x, y) = (pixel_x/map.tile_width, pixel_y/map.tile_height);
+        match world.put(obj, x, y) {
+            Ok(_)   =>  (),
+            Err(_)  =>  return Err(format!("Object {} is outside the map at ({},{})", id, x, y)),
+        };
+    }
+    Ok(world)
+}
+
+//Purpose: escapes text for use inside an xml attribute
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;").replace('"', "&quot;")
+}
+
+/*
+Signature:  to_tmx(&World,&TileIdMap,u32,u32)
+Purpose:    Write a world as a Tiled TMX map with an embedded tileset, one csv tile layer per stacked layer of terrain and an object layer for everything else
+Inputs:     The world, the tile ids to use and the width and height of a tile in pixels
+Outputs:    The xml text, a String if some terrain has no tile id
+*/
+pub fn to_tmx(world: &World, ids: &TileIdMap, tile_width: u32, tile_height: u32) -> Result<String, String> {
+    let map = match split(world, ids, tile_width, tile_height) {
+        Ok(val) =>  val,
+        Err(e)  =>  return Err(e),
+    };
+    let tile_count = ids.entries.iter().map(|e| e.0).max().unwrap_or(0);
+    let mut xml = String::new();
+    xml.push_str("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+    xml.push_str(&format!("<map version=\"1.10\" orientation=\"orthogonal\" renderorder=\"right-down\" width=\"{}\" height=\"{}\" tilewidth=\"{}\" tileheight=\"{}\" infinite=\"0\" nextlayerid=\"{}\" nextobjectid=\"{}\">\n",
+        map.width, map.height, tile_width, tile_height, map.layers.len()+2, map.next_object_id));
+    xml.push_str(&format!(" <tileset firstgid=\"1\" name=\"pgmap\" tilewidth=\"{}\" tileheight=\"{}\" tilecount=\"{}\" columns=\"0\">\n", tile_width, tile_height, tile_count));
+    for &(id, obj) in ids.entries.iter() {
+        xml.push_str(&format!("  <tile id=\"{}\" type=\"{}\"/>\n", id-1, obj.name()));
+    }
+    xml.push_str(" </tileset>\n");
+    for (index, layer) in map.layers.iter().enumerate() {
+        xml.push_str(&format!(" <layer id=\"{}\" name=\"layer{}\" width=\"{}\" height=\"{}\">\n  <data encoding=\"csv\">\n", index+1, index, map.width, map.height));
+        let rows: Vec<String> = layer.chunks(map.width as usize).map(|row| row.iter().map(|id| id.to_string()).collect::<Vec<String>>().join(",")).collect();
+        xml.push_str(&rows.join(",\n"));
+        xml.push_str("\n</data>\n </layer>\n");
+    }
+    xml.push_str(&format!(" <objectgroup id=\"{}\" name=\"entities\">\n", map.layers.len()+1));
+    for &(id, ref name, x, y) in map.objects.iter() {
+        xml.push_str(&format!("  <object id=\"{}\" type=\"{}\" x=\"{}\" y=\"{}\" width=\"{}\" height=\"{}\"/>\n", id, escape(name), x, y, tile_width, tile_height));
+    }
+    xml.push_str(" </objectgroup>\n</map>\n");
+    Ok(xml)
+}
+
+/*
+Signature:  to_tmj(&World,&TileIdMap,u32,u32)
+Purpose:    Write a world as a Tiled JSON map, laid out the same way as to_tmx
+Inputs:     The world, the tile ids to use and the width and height of a tile in pixels
+Outputs:    The json text, a String if some terrain has no tile id
+*/
+pub fn to_tmj(world: &World, ids: &TileIdMap, tile_width: u32, tile_height: u32) -> Result<String, String> {
+    let map = match split(world, ids, tile_width, tile_height) {
+        Ok(val) =>  val,
+        Err(e)  =>  return Err(e),
+    };
+    let number = |n: u32| Json::U64(n as u64);
+    let text = |s: &str| Json::String(s.to_string());
+    let mut tiles = Vec::new();
+    for &(id, obj) in ids.entries.iter() {
+        let mut tile = BTreeMap::new();
+        tile.insert("id".to_string(), number(id-1));
+        tile.insert("type".to_string(), text(obj.name()));
+        tiles.push(Json::Object(tile));
+    }
+    let mut tileset = BTreeMap::new();
+    tileset.insert("firstgid".to_string(), number(1));
+    tileset.insert("name".to_string(), text("pgmap"));
+    tileset.insert("tilewidth".to_string(), number(tile_width));
+    tileset.insert("tileheight".to_string(), number(tile_height));
+    tileset.insert("tilecount".to_string(), number(ids.entries.iter().map(|e| e.0).max().unwrap_or(0)));
+    tileset.insert("columns".to_string(), number(0));
+    tileset.insert("tiles".to_string(), Json::Array(tiles));
+
+    let mut layers = Vec::new();
+    for (index, layer) in map.layers.iter().enumerate() {
+        let mut layer_json = BTreeMap::new();
+        layer_json.insert("id".to_string(), number(index as u32+1));
+        layer_json.insert("name".to_string(), Json::String(format!("layer{}", index)));
+        layer_json.insert("type".to_string(), text("tilelayer"));
+        layer_json.insert("width".to_string(), number(map.width));
+        layer_json.insert("height".to_string(), number(map.height));
+        layer_json.insert("x".to_string(), number(0));
+        layer_json.insert("y".to_string(), number(0));
+        layer_json.insert("opacity".to_string(), number(1));
+        layer_json.insert("visible".to_string(), Json::Boolean(true));
+        layer_json.insert("data".to_string(), Json::Array(layer.iter().map(|&id| number(id)).collect()));
+        layers.push(Json::Object(layer_json));
+    }
+    let mut objects = Vec::new();
+    for &(id, ref name, x, y) in map.objects.iter() {
+        let mut object = BTreeMap::new();
+        object.insert("id".to_string(), number(id));
+        object.insert("name".to_string(), text(""));
+        object.insert("type".to_string(), text(name));
+        object.insert("x".to_string(), number(x));
+        object.insert("y".to_string(), number(y));
+        object.insert("width".to_string(), number(tile_width));
+        object.insert("height".to_string(), number(tile_height));
+        object.insert("rotation".to_string(), number(0));
+        object.insert("visible".to_string(), Json::Boolean(true));
+        objects.push(Json::Object(object));
+    }
+    let mut object_layer = BTreeMap::new();
+    object_layer.insert("id".to_string(), number(map.layers.len() as u32+1));
+    object_layer.insert("name".to_string(), text("entities"));
+    object_layer.insert("type".to_string(), text("objectgroup"));
+    object_layer.insert("draworder".to_string(), text("topdown"));
+    object_layer.insert("x".to_string(), number(0));
+    object_layer.insert("y".to_string(), number(0));
+    object_layer.insert("opacity".to_string(), number(1));
+    object_layer.insert("visible".to_string(), Json::Boolean(true));
+    object_layer.insert("objects".to_string(), Json::Array(objects));
+    layers.push(Json::Object(object_layer));
+
+    let mut root = BTreeMap::new();
+    root.insert("type".to_string(), text("map"));
+    root.insert("version".to_string(), text("1.10"));
+    root.insert("orientation".to_string(), text("orthogonal"));
+    root.insert("renderorder".to_string(), text("right-down"));
+    root.insert("width".to_string(), number(map.width));
+    root.insert("height".to_string(), number(map.height));
+    root.insert("tilewidth".to_string(), number(tile_width));
+    root.insert("tileheight".to_string(), number(tile_height));
+    root.insert("infinite".to_string(), Json::Boolean(false));
+    root.insert("nextlayerid".to_string(), number(map.layers.len() as u32+2));
+    root.insert("nextobjectid".to_string(), number(map.next_object_id));
+    root.insert("tilesets".to_string(), Json::Array(vec![Json::Object(tileset)]));
+    root.insert("layers".to_string(), Json::Array(layers));
+    Ok(json::encode(&Json::Object(root)).unwrap())
+}
+
+//Purpose: reads a whole number out of a json object, accepting the floats Tiled writes for positions
+fn json_number(object: &Object, field: &str) -> Result<u32, String> {
+    match object.get(&field.to_string()).and_then(|j| j.as_f64()) {
+        Some(val) if val >= 0.0 =>  Ok(val as u32),
+        _                       =>  Err(format!("The Tiled map doesn't have a valid {} field", field)),
+    }
+}
+
+/*
+Signature:  from_tmj(&str,&TileIdMap)
+Purpose:    Creation of a new world object from a Tiled JSON map
+Inputs:     The json text and the tile ids it uses, only the first tileset's firstgid is taken into account
+Outputs:    The world with tile layers put bottom to top and objects on top in id order, a String describing what couldn't be read
+*/
+pub fn from_tmj(text: &str, ids: &TileIdMap) -> Result<World, String> {
+    let root = match Json::from_str(text) {
+        Ok(Json::Object(val))   =>  val,
+        _                       =>  return Err("The Tiled map is not a json object".to_string()),
+    };
+    let mut map = match (json_number(&root, "width"), json_number(&root, "height"), json_number(&root, "tilewidth"), json_number(&root, "tileheight")) {
+        (Ok(w), Ok(h), Ok(tw), Ok(th)) if tw > 0 && th > 0  =>  TiledMap{width: w, height: h, tile_width: tw, tile_height: th, layers: Vec::new(), objects: Vec::new(), next_object_id: 0},
+        _                                                   =>  return Err("The Tiled map doesn't have a valid size and tile size".to_string()),
+    };
+    let first_gid = match root.get(&"tilesets".to_string()).and_then(|t| t.as_array()).and_then(|t| t.first()).and_then(|t| t.as_object()) {
+        Some(tileset)   =>  json_number(tileset, "firstgid").unwrap_or(1),
+        None            =>  1,
+    };
+    let layers = match root.get(&"layers".to_string()) {
+        Some(&Json::Array(ref val)) =>  val,
+        _                           =>  return Err("The Tiled map doesn't have a valid layers field".to_string()),
+    };
+    for layer_json in layers.iter() {
+        let layer = match layer_json.as_object() {
+            Some(val)   =>  val,
+            None        =>  return Err("The Tiled map has a layer that is not a json object".to_string()),
+        };
+        match layer.get(&"type".to_string()).and_then(|t| t.as_string()) {
+            Some("tilelayer")   =>  {
+                let data = match layer.get(&"data".to_string()) {
+                    Some(&Json::Array(ref val)) =>  val,
+                    _                           =>  return Err("The Tiled map has a tile layer without csv data, base64 layers are not supported".to_string()),
+                };
+                let mut tiles = Vec::new();
+                for id in data.iter() {
+                    match id.as_u64() {
+                        Some(val)   =>  tiles.push(val as u32),
+                        None        =>  return Err("The Tiled map has a tile id that is not a number".to_string()),
+                    };
+                }
+                map.layers.push(tiles);
+            },
+            Some("objectgroup") =>  {
+                let objects = match layer.get(&"objects".to_string()) {
+                    Some(&Json::Array(ref val)) =>  val,
+                    _                           =>  continue,
+                };
+                for object_json in objects.iter() {
+                    let object = match object_json.as_object() {
+                        Some(val)   =>  val,
+                        None        =>  return Err("The Tiled map has an object that is not a json object".to_string()),
+                    };
+                    let name = match object.get(&"type".to_string()).or(object.get(&"class".to_string())).and_then(|t| t.as_string()) {
+                        Some(val)   =>  val.to_string(),
+                        None        =>  return Err("The Tiled map has an object without a type".to_string()),
+                    };
+                    let (id, x, y) = match (json_number(object, "id"), json_number(object, "x"), json_number(object, "y")) {
+                        (Ok(id), Ok(x), Ok(y))  =>  (id, x, y),
+                        _                       =>  return Err(format!("The Tiled map has the {} object without an id and position", name)),
+                    };
+                    map.objects.push((id, name, x, y));
+                }
+            },
+            _                   =>  (),
+        };
+    }
+    join(&map, ids, first_gid)
+}
+
+//One piece of an xml document
+#[derive(Clone, PartialEq, Debug)]
+enum XmlToken {
+    Open{name: String, attributes: Vec<(String, String)>, empty: bool},
+    Close(String),
+    Text(String),
+}
+
+fn unescape(text: &str) -> String {
+    text.replace("&lt;", "<").replace("&gt;", ">").replace("&quot;", "\"").replace("&apos;", "'").replace("&amp;", "&")
+}
+
+//Purpose: splits xml into tags and text, enough of it to read back what to_tmx and Tiled itself write
+fn tokenize(xml: &str) -> Result<Vec<XmlToken>, String> {
+    let mut tokens = Vec::new();
+    let mut rest = xml;
+    while rest.len() != 0 {
+        if rest.starts_with("<?") || rest.starts_with("<!--") {
+            let end = if rest.starts_with("<?") { "?>" } else { "-->" };
+            match rest.find(end) {
+                Some(index) =>  rest = &rest[index+end.len()..],
+                None        =>  return Err("The TMX map has an unterminated declaration or comment".to_string()),
+            };
+        }
+        else if rest.starts_with('<') {
+            let index = match rest.find('>') {
+                Some(val)   =>  val,
+                None        =>  return Err("The TMX map has an unterminated tag".to_string()),
+            };
+            let tag = &rest[1..index];
+            rest = &rest[index+1..];
+            if tag.starts_with('/') {
+                tokens.push(XmlToken::Close(tag[1..].trim().to_string()));
+                continue;
+            }
+            let empty = tag.ends_with('/');
+            let tag = if empty { &tag[..tag.len()-1] } else { tag };
+            let name_end = tag.find(char::is_whitespace).unwrap_or(tag.len());
+            let name = tag[..name_end].to_string();
+            let mut attributes = Vec::new();
+            let mut attribute_text = tag[name_end..].trim_start();
+            while attribute_text.len() != 0 {
+                let equals = match attribute_text.find('=') {
+                    Some(val)   =>  val,
+                    None        =>  return Err(format!("The TMX map has a malformed attribute in the {} tag", name)),
+                };
+                let key = attribute_text[..equals].trim().to_string();
+                let value_text = attribute_text[equals+1..].trim_start();
+                let quote = match value_text.chars().next() {
+                    Some(c) if c == '"' || c == '\'' =>  c,
+                    _                               =>  return Err(format!("The TMX map has an unquoted attribute {} in the {} tag", key, name)),
+                };
+                let close = match value_text[1..].find(quote) {
+                    Some(val)   =>  val+1,
+                    None        =>  return Err(format!("The TMX map has an unterminated attribute {} in the {} tag", key, name)),
+                };
+                attributes.push((key, unescape(&value_text[1..close])));
+                attribute_text = value_text[close+1..].trim_start();
+            }
+            tokens.push(XmlToken::Open{name: name, attributes: attributes, empty: empty});
+        }
+        else {
+            let index = rest.find('<').unwrap_or(rest.len());
+            let text = rest[..index].trim();
+            if text.len() != 0 {
+                tokens.push(XmlToken::Text(unescape(text)));
+            }
+            rest = &rest[index..];
+        }
+    }
+    Ok(tokens)
+}
+
+fn attribute<'a>(attributes: &'a Vec<(String, String)>, key: &str) -> Option<&'a str> {
+    attributes.iter().find(|a| a.0 == key).map(|a| a.1.as_ref())
+}
+
+fn number_attribute(attributes: &Vec<(String, String)>, key: &str, tag: &str) -> Result<u32, String> {
+    match attribute(attributes, key).and_then(|v| v.parse::<f64>().ok()) {
+        Some(val) if val >= 0.0 =>  Ok(val as u32),
+        _                       =>  Err(format!("The TMX map has a {} tag without a valid {} attribute", tag, key)),
+    }
+}
+
+/*
+Signature:  from_tmx(&str,&TileIdMap)
+Purpose:    Creation of a new world object from a Tiled TMX map
+Inputs:     The xml text with csv encoded layers and the tile ids it uses, only the first tileset's firstgid is taken into account
+Outputs:    The world with tile layers put bottom to top and objects on top in id order, a String describing what couldn't be read
+*/
+pub fn from_tmx(xml: &str, ids: &TileIdMap) -> Result<World, String> {
+    let tokens = match tokenize(xml) {
+        Ok(val) =>  val,
+        Err(e)  =>  return Err(e),
+    };
+    let mut map = TiledMap{width: 0, height: 0, tile_width: 0, tile_height: 0, layers: Vec::new(), objects: Vec::new(), next_object_id: 0};
+    let mut first_gid = None;
+    let mut found_map = false;
+    let mut in_data = false;
+    for token in tokens.iter() {
+        match *token {
+            XmlToken::Open{ref name, ref attributes, empty} =>  {
+                match name.as_ref() {
+                    "map"       =>  {
+                        found_map = true;
+                        match (number_attribute(attributes, "width", "map"), number_attribute(attributes, "height", "map"), number_attribute(attributes, "tilewidth", "map"), number_attribute(attributes, "tileheight", "map")) {
+                            (Ok(w), Ok(h), Ok(tw), Ok(th)) if tw > 0 && th > 0  =>  {
+                                map.width = w;
+                                map.height = h;
+                                map.tile_width = tw;
+                                map.tile_height = th;
+                            },
+                            _                                                   =>  return Err("The TMX map tag doesn't have a valid size and tile size".to_string()),
+                        };
+                    },
+                    "tileset"   =>  {
+                        if first_gid.is_none() {
+                            first_gid = Some(number_attribute(attributes, "firstgid", "tileset").unwrap_or(1));
+                        }
+                    },
+                    "data"      =>  {
+                        match attribute(attributes, "encoding") {
+                            Some("csv") =>  (),
+                            _           =>  return Err("The TMX map has a layer that is not csv encoded, only csv layers are supported".to_string()),
+                        };
+                        map.layers.push(Vec::new());
+                        in_data = !empty;
+                    },
+                    "object"    =>  {
+                        let name = match attribute(attributes, "type").or(attribute(attributes, "class")) {
+                            Some(val)   =>  val.to_string(),
+                            None        =>  return Err("The TMX map has an object without a type".to_string()),
+                        };
+                        match (number_attribute(attributes, "id", "object"), number_attribute(attributes, "x", "object"), number_attribute(attributes, "y", "object")) {
+                            (Ok(id), Ok(x), Ok(y))  =>  map.objects.push((id, name, x, y)),
+                            _                       =>  return Err(format!("The TMX map has the {} object without an id and position", name)),
+                        };
+                    },
+                    _           =>  (),
+                };
+            },
+            XmlToken::Text(ref text) if in_data    =>  {
+                let layer = map.layers.last_mut().unwrap();
+                for id in text.split(',').map(|i| i.trim()).filter(|i| i.len() != 0) {
+                    match id.parse::<u32>() {
+                        Ok(val) =>  layer.push(val),
+                        Err(_)  =>  return Err(format!("The TMX map has the tile id {} that is not a number", id)),
+                    };
+                }
+            },
+            XmlToken::Close(ref name) if name == "data" =>  in_data = false,
+            _                                           =>  (),
+        };
+    }
+    if !found_map {
+        return Err("The TMX text doesn't have a map tag".to_string());
+    }
+    join(&map, ids, first_gid.unwrap_or(1))
+}
@@ -0,0 +1,114 @@
+use std::collections::HashMap;
+use world::{
+    World,
+    WObject,
+};
+use world::format::{
+    MAX_CELLS,
+    MAX_SIDE,
+};
+use tile::Type;
+
+//Clockwise rotations
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum Rotation {
+    Quarter,
+    Half,
+    ThreeQuarter,
+}
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum Mirror {
+    //flip left to right
+    Horizontal,
+    //flip top to bottom
+    Vertical,
+}
+
+//Purpose: turns a horizontal wall into a vertical one and the other way around
+fn swap_wall(object: &WObject) -> WObject {
+    let obj = match object.obj {
+        Type::HorizontalWall    =>  Type::VerticalWall,
+        Type::VerticalWall      =>  Type::HorizontalWall,
+        other                   =>  other,
+    };
+    WObject{uid: object.uid, obj: obj}
+}
+
+//Purpose: copies every cell of a world to a new position in a world of the given size
+fn remap<F>(world: &World, width: u32, height: u32, swap_walls: bool, position: F) -> World where F: Fn(u32, u32) -> (u32, u32) {
+    let mut data = HashMap::new();
+    for x in 0..width {
+        for y in 0..height {
+            data.insert((x,y), Vec::new());
+        }
+    }
+    for (&(x, y), z_level) in world.data.iter() {
+        let new_z_level = if swap_walls {
+            z_level.iter().map(swap_wall).collect()
+        }
+        else {
+            z_level.clone()
+        };
+        data.insert(position(x, y), new_z_level);
+    }
+    World::from_parts(width, height, world.current_uid, data)
+}
+
+impl World {
+    /*
+    Signature:  rotate(Rotation)
+    Purpose:    Create a copy of the world turned clockwise, swapping horizontal and vertical walls on quarter turns
+    Inputs:     How far to turn
+    Outputs:    The rotated world, with width and height swapped on quarter turns and every uid kept
+    */
+    pub fn rotate(&self, rotation: Rotation) -> World {
+        let (w, h) = (self.w, self.h);
+        match rotation {
+            Rotation::Quarter       =>  remap(self, h, w, true, |x, y| (h-1-y, x)),
+            Rotation::Half          =>  remap(self, w, h, false, |x, y| (w-1-x, h-1-y)),
+            Rotation::ThreeQuarter  =>  remap(self, h, w, true, |x, y| (y, w-1-x)),
+        }
+    }
+
+    /*
+    Signature:  mirror(Mirror)
+    Purpose:    Create a flipped copy of the world
+    Inputs:     Which way to flip
+    Outputs:    The mirrored world with every uid kept
+    */
+    pub fn mirror(&self, mirror: Mirror) -> World {
+        let (w, h) = (self.w, self.h);
+        match mirror {
+            Mirror::Horizontal  =>  remap(self, w, h, false, |x, y| (w-1-x, y)),
+            Mirror::Vertical    =>  remap(self, w, h, false, |x, y| (x, h-1-y)),
+        }
+    }
+
+    /*
+    Signature:  crop(u32,u32,u32,u32)
+    Purpose:    Create a copy of part of the world, dropping everything outside it
+    Inputs:     The x and y coordinates of the upper left corner followed by the width and height to keep
+    Outputs:    The cropped world with every uid kept, a String if the region is not inside the world
+    */
+    pub fn crop(&self, x: u32, y: u32, width: u32, height: u32) -> Result<World, String> {
+        self.extract(x, y, width, height)
+    }
+
+    /*
+    Signature:  pad(u32,u32,u32,u32)
+    Purpose:    Create a copy of the world grown with empty cells on each side
+    Inputs:     The number of columns to add on the left and right followed by the number of rows to add on the top and bottom
+    Outputs:    The grown world with every object shifted by the left and top padding, a String if it would be larger than a save can hold
+    */
+    pub fn pad(&self, left: u32, right: u32, top: u32, bottom: u32) -> Result<World, String> {
+        let width = self.w.checked_add(left).and_then(|w| w.checked_add(right));
+        let height = self.h.checked_add(top).and_then(|h| h.checked_add(bottom));
+        match (width, height) {
+            (Some(w), Some(h)) if w <= MAX_SIDE && h <= MAX_SIDE && w as u64*h as u64 <= MAX_CELLS    =>
+                Ok(remap(self, w, h, false, |x, y| (x+left, y+top))),
+            _   =>  Err(format!("Padding the {}x{} world by {}, {}, {} and {} would make it larger than the {}x{} and {} cell limit",
+                self.w, self.h, left, right, top, bottom, MAX_SIDE, MAX_SIDE, MAX_CELLS)),
+        }
+    }
+}